@@ -0,0 +1,76 @@
+#!/usr/bin/env python3
+"""Fill in the sha256 and size of every file in the bundled model catalog.
+
+LFS files take the SHA-256 Hugging Face publishes as their LFS oid; small
+files stored in git are downloaded and hashed. Entries are rewritten in
+place, one file object per line, so the rest of the catalog keeps its layout.
+
+Usage: scripts/catalog-checksums.py [path/to/models.json]
+"""
+
+import hashlib
+import json
+import re
+import sys
+import urllib.parse
+import urllib.request
+from pathlib import Path
+
+DEFAULT_CATALOG = Path(__file__).resolve().parent.parent / "src-tauri" / "catalog" / "models.json"
+RESOLVE = re.compile(r"^https://huggingface\.co/(?P<repo>[^/]+/[^/]+)/resolve/(?P<rev>[^/]+)/(?P<path>.+)$")
+FILE_LINE = re.compile(r'^(?P<indent>\s*)\{ "url": "(?P<url>[^"]+)"(?P<rest>.*?)\s*\}(?P<comma>,?)$')
+
+
+def paths_info(repo, rev, paths):
+    url = f"https://huggingface.co/api/models/{repo}/paths-info/{urllib.parse.quote(rev, safe='')}"
+    body = urllib.parse.urlencode([("paths", p) for p in paths]).encode()
+    with urllib.request.urlopen(urllib.request.Request(url, data=body)) as resp:
+        return {entry["path"]: entry for entry in json.load(resp)}
+
+
+def hash_url(url):
+    sha, size = hashlib.sha256(), 0
+    with urllib.request.urlopen(url) as resp:
+        while chunk := resp.read(1 << 20):
+            sha.update(chunk)
+            size += len(chunk)
+    return sha.hexdigest(), size
+
+
+def checksum(url, cache):
+    m = RESOLVE.match(url)
+    if not m:
+        sys.exit(f"Not a Hugging Face resolve URL: {url}")
+    key = (m["repo"], m["rev"])
+    if key not in cache:
+        cache[key] = {}
+    info = cache[key].get(m["path"])
+    if info is None:
+        cache[key].update(paths_info(m["repo"], m["rev"], [m["path"]]))
+        info = cache[key].get(m["path"])
+    if info is None:
+        sys.exit(f"Not found on Hugging Face: {url}")
+    lfs = info.get("lfs")
+    if lfs:
+        return lfs["oid"], lfs["size"]
+    return hash_url(url)
+
+
+def main():
+    path = Path(sys.argv[1]) if len(sys.argv) > 1 else DEFAULT_CATALOG
+    lines = path.read_text().splitlines()
+    cache = {}
+    for i, line in enumerate(lines):
+        m = FILE_LINE.match(line)
+        if not m:
+            continue
+        rest = re.sub(r',\s*"(sha256|size)":\s*("[^"]*"|\d+)', "", m["rest"])
+        sha256, size = checksum(m["url"], cache)
+        lines[i] = f'{m["indent"]}{{ "url": "{m["url"]}"{rest}, "sha256": "{sha256}", "size": {size} }}{m["comma"]}'
+        print(f"{size:>12}  {sha256}  {m['url']}", file=sys.stderr)
+    path.write_text("\n".join(lines) + "\n")
+    json.loads(path.read_text())
+
+
+if __name__ == "__main__":
+    main()
@@ -1,5 +1,7 @@
 #import <AppKit/AppKit.h>
 #include <dispatch/dispatch.h>
+#include <stdlib.h>
+#include <string.h>
 
 bool copy_string_to_pasteboard(const char *str) {
     if (!str) return false;
@@ -22,3 +24,90 @@ bool copy_string_to_pasteboard(const char *str) {
 
     return result;
 }
+
+// Returns a malloc'd UTF-8 copy of the pasteboard's string, or NULL if it
+// holds no text. Free with free_pasteboard_string.
+char *read_string_from_pasteboard(void) {
+    __block char *result = NULL;
+
+    void (^readString)(void) = ^{
+        NSString *str = [[NSPasteboard generalPasteboard] stringForType:NSPasteboardTypeString];
+        if (str) {
+            result = strdup([str UTF8String]);
+        }
+    };
+
+    if ([NSThread isMainThread]) {
+        readString();
+    } else {
+        dispatch_sync(dispatch_get_main_queue(), readString);
+    }
+
+    return result;
+}
+
+void free_pasteboard_string(char *str) {
+    free(str);
+}
+
+// Runs block on the main thread, synchronously.
+static void on_main_thread(void (^block)(void)) {
+    if ([NSThread isMainThread]) {
+        block();
+    } else {
+        dispatch_sync(dispatch_get_main_queue(), block);
+    }
+}
+
+long pasteboard_change_count(void) {
+    __block long count = 0;
+    on_main_thread(^{
+        count = (long)[[NSPasteboard generalPasteboard] changeCount];
+    });
+    return count;
+}
+
+// Copies every item on the pasteboard with the data for each of its types,
+// so images, files and rich text survive a round trip. Returns a retained
+// NSArray, or NULL if the pasteboard is empty. Pass it to restore_pasteboard
+// or free_pasteboard_snapshot.
+void *snapshot_pasteboard(void) {
+    __block NSMutableArray *snapshot = nil;
+    on_main_thread(^{
+        NSArray *items = [[NSPasteboard generalPasteboard] pasteboardItems];
+        if ([items count] == 0) return;
+        snapshot = [[NSMutableArray alloc] initWithCapacity:[items count]];
+        for (NSPasteboardItem *item in items) {
+            NSPasteboardItem *copy = [[NSPasteboardItem alloc] init];
+            for (NSPasteboardType type in [item types]) {
+                NSData *data = [item dataForType:type];
+                if (data) {
+                    [copy setData:data forType:type];
+                }
+            }
+            [snapshot addObject:copy];
+            [copy release];
+        }
+    });
+    return snapshot;
+}
+
+// Writes a snapshot back to the pasteboard and releases it. A NULL
+// snapshot (the pasteboard was empty) clears it.
+bool restore_pasteboard(void *snapshot) {
+    NSArray *items = (NSArray *)snapshot;
+    __block bool result = false;
+    on_main_thread(^{
+        NSPasteboard *pb = [NSPasteboard generalPasteboard];
+        [pb clearContents];
+        result = items ? [pb writeObjects:items] : true;
+    });
+    [items release];
+    return result;
+}
+
+void free_pasteboard_snapshot(void *snapshot) {
+    if (snapshot) {
+        [(NSArray *)snapshot release];
+    }
+}
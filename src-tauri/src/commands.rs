@@ -1,4 +1,4 @@
-use crate::{escape_monitor, frontmost, history, model_registry, paster, recorder, state, transcriber};
+use crate::{escape_monitor, frontmost, hardware, history, model_bundle, model_registry, paster, polish, postprocess, profiles, recorder, redact, refine, state, storage, transcriber, typer};
 use crate::state::AppState;
 use tauri::{Emitter, Manager};
 use tauri_plugin_store::StoreExt;
@@ -47,8 +47,13 @@ pub async fn stop_recording(
 ) -> Result<String, String> {
     // Capture frontmost app before any processing
     let (app_name, window_title) = frontmost::get_frontmost_app();
+    let profile = profiles::resolve(&app, app_name.as_deref(), window_title.as_deref());
+    if let Some(p) = &profile {
+        eprintln!("[audioshift] Using profile {:?}", p.name);
+    }
 
     escape_monitor::stop();
+    let hotkey_mode = state.take_dictation_mode();
     let samples = recorder::stop_recording(&state).map_err(|e| e.to_string())?;
 
     state.set_status(state::Status::Transcribing);
@@ -56,25 +61,47 @@ pub async fn stop_recording(
 
     let store = app.store("settings.json").ok();
 
-    let live_model = store
+    let live_model = profile
         .as_ref()
-        .and_then(|s| s.get("liveModel"))
-        .and_then(|v| v.as_str().map(String::from))
+        .and_then(|p| p.live_model.clone())
+        .or_else(|| {
+            store
+                .as_ref()
+                .and_then(|s| s.get("liveModel"))
+                .and_then(|v| v.as_str().map(String::from))
+        })
         .unwrap_or_else(|| model_registry::DEFAULT_MODEL_ID.to_string());
 
     // Read language settings (only meaningful for Whisper models)
-    let language = store
+    let language = profile
         .as_ref()
-        .and_then(|s| s.get("transcriptionLanguage"))
-        .and_then(|v| v.as_str().map(String::from))
+        .and_then(|p| p.language.clone())
+        .or_else(|| {
+            store
+                .as_ref()
+                .and_then(|s| s.get("transcriptionLanguage"))
+                .and_then(|v| v.as_str().map(String::from))
+        })
         .unwrap_or_else(|| "auto".to_string());
     let language = if language == "auto" { None } else { Some(language) };
 
-    let translate = store
+    let translate = profile.as_ref().and_then(|p| p.translate).unwrap_or_else(|| {
+        store
+            .as_ref()
+            .and_then(|s| s.get("translateToEnglish"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    });
+
+    let paste_mode = profile
         .as_ref()
-        .and_then(|s| s.get("translateToEnglish"))
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+        .and_then(|p| p.paste_mode)
+        .unwrap_or_else(|| paster::paste_mode(&app, auto_paste));
+    let prompt = profile.as_ref().and_then(|p| p.prompt.clone());
+    let mode = hotkey_mode
+        .or_else(|| profile.as_ref().and_then(|p| p.mode))
+        .unwrap_or_default();
+    let overrides = profile.as_ref().map(|p| p.post_processing.clone()).unwrap_or_default();
 
     let save_history = store
         .as_ref()
@@ -84,35 +111,111 @@ pub async fn stop_recording(
 
     // Clone samples before transcription (transcriber consumes them) so we can save audio
     let samples_for_save = if save_history { Some(samples.clone()) } else { None };
+    let samples_for_refine = refine::two_pass_model(&app).map(|_| samples.clone());
     let duration_ms = (samples.len() as u64 * 1000) / 16000;
 
     let transcribe_start = std::time::Instant::now();
-    let text = transcriber::transcribe_from_samples(&app, samples, &live_model, language.clone(), translate)
-        .await
-        .map_err(|e| e.to_string())?;
+    let route = if live_model == model_registry::AUTO_MODEL_ID {
+        Some(transcriber::route_auto(&app, &samples, language.as_deref(), translate).await)
+    } else {
+        None
+    };
+    let model_id = route.as_ref().map_or(live_model, |r| r.model_id.clone());
+    // Whisper would otherwise detect the language a second time
+    let transcribe_language = route.as_ref().and_then(|r| r.language.clone()).or(language.clone());
+    let raw_text = transcriber::transcribe_from_samples(
+        &app,
+        samples,
+        &model_id,
+        transcribe_language.clone(),
+        translate,
+        prompt.clone(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    // A model that only knows one language settles it without detection
+    let text_language = transcribe_language.clone().or_else(|| {
+        model_registry::find_model(&model_id).and_then(|m| match m.languages.as_slice() {
+            [only] if only != "*" => Some(only.clone()),
+            _ => None,
+        })
+    });
+    let pipeline_ctx = postprocess::Context {
+        language: text_language,
+        mode,
+        list: postprocess::markdown::list_state(app_name.as_deref()),
+        app_name: app_name.clone(),
+        placeholders: Default::default(),
+        overrides,
+    };
+    let pipeline = postprocess::Pipeline::load(&app).with_overrides(&pipeline_ctx.overrides);
+    let text = pipeline.run_marked(&raw_text, &pipeline_ctx);
+    // A rewrite would mangle code and Markdown structure
+    let (text, polished) = match mode {
+        postprocess::Mode::Prose => polish::apply(&app, &text).await,
+        _ => (text, None),
+    };
+    // Snippets expand after the polish so the LLM can't reword them
+    let text = pipeline.expand_snippets(&text, &pipeline_ctx);
+    let polished = polished.map(|p| polish::Polish {
+        original_text: pipeline.expand_snippets(&p.original_text, &pipeline_ctx),
+        ..p
+    });
+    let text = redact::pasted(&app, text);
     let processing_time_ms = transcribe_start.elapsed().as_millis() as u64;
 
+    let mut delivery = Ok(());
     if !text.is_empty() {
+        // Prose that happens to start a line with "- " isn't a list to continue
+        if mode == postprocess::Mode::Markdown {
+            postprocess::markdown::remember(app_name.as_deref(), &text);
+        }
+        let mut entry_id = None;
         if save_history {
             if let Some(audio_samples) = samples_for_save {
-                history::add_entry(&app, history::RecordingInfo {
+                entry_id = history::add_entry(&app, history::RecordingInfo {
                     samples: audio_samples,
                     text: text.clone(),
+                    raw_text,
                     app_name,
                     window_title,
                     duration_ms,
                     processing_time_ms,
-                    model_id: live_model.clone(),
+                    model_id: model_id.clone(),
                     language,
                     translate,
+                    route,
+                    profile: profile.as_ref().map(|p| p.name.clone()),
+                    polish: polished,
                 });
             }
         }
 
-        if auto_paste {
-            paster::paste_text(&text).map_err(|e| e.to_string())?;
-        } else {
-            paster::copy_to_clipboard(&text).map_err(|e| e.to_string())?;
+        let paste_chord = profile.as_ref().and_then(|p| p.paste_chord);
+        let (output_app, output_text) = (app.clone(), text.clone());
+        let delivered = tokio::task::spawn_blocking(move || {
+            paster::output(&output_app, &output_text, paste_mode, paste_chord)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        if let Err(e) = &delivered {
+            eprintln!("[audioshift] Failed to deliver text: {:#}", e);
+        }
+        delivery = delivered;
+
+        if let Some(samples) = samples_for_refine {
+            refine::spawn_refinement(&app, refine::Draft {
+                entry_id,
+                samples,
+                text: text.clone(),
+                model_id: model_id.clone(),
+                translate,
+                prompt,
+                pipeline,
+                context: pipeline_ctx,
+                paste_mode: profile.as_ref().and_then(|p| p.paste_mode),
+                paste_chord,
+            });
         }
     }
 
@@ -122,6 +225,8 @@ pub async fn stop_recording(
     state.set_status(state::Status::Idle);
     let _ = app.emit("status-changed", "idle");
 
+    // Reported once the dictation is saved and the status is reset
+    delivery.map_err(|e| format!("{:#}", e))?;
     Ok(text)
 }
 
@@ -131,6 +236,7 @@ pub async fn cancel_recording(
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     escape_monitor::stop();
+    state.set_dictation_mode(None);
     recorder::cancel_recording(&state).map_err(|e| e.to_string())?;
     let _ = app.emit("status-changed", "idle");
     Ok(())
@@ -148,6 +254,34 @@ pub fn set_hotkey(app: tauri::AppHandle, state: tauri::State<'_, AppState>, shor
     Ok(())
 }
 
+/// Extra shortcuts that start a dictation in a given mode.
+#[tauri::command]
+pub fn get_mode_hotkeys(app: tauri::AppHandle) -> std::collections::BTreeMap<postprocess::Mode, String> {
+    crate::hotkey::mode_hotkeys(&app)
+}
+
+/// Set or clear (`None`) the shortcut that starts dictation in `mode`.
+#[tauri::command]
+pub fn set_mode_hotkey(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    mode: postprocess::Mode,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let shortcut = shortcut.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(s) = &shortcut {
+        if s.eq_ignore_ascii_case(&state.hotkey()) {
+            return Err("That shortcut is already the main dictation hotkey".to_string());
+        }
+    }
+    let mut hotkeys = crate::hotkey::mode_hotkeys(&app);
+    match shortcut {
+        Some(s) => hotkeys.insert(mode, s),
+        None => hotkeys.remove(&mode),
+    };
+    crate::hotkey::set_mode_hotkeys(&app, &hotkeys).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn check_microphone_permission() -> String {
     #[cfg(target_os = "macos")]
@@ -368,28 +502,86 @@ pub struct ModelStatusEntry {
     engine: model_registry::Engine,
     description: String,
     size_label: String,
+    languages: Vec<String>,
+    capabilities: model_registry::Capabilities,
     ready: bool,
+    state: model_registry::ModelState,
     disk_size: u64,
     path: String,
+    /// Set while the model is loaded in memory.
+    runtime: Option<transcriber::LoadedModelInfo>,
 }
 
 #[tauri::command]
 pub fn get_all_models_status() -> Vec<ModelStatusEntry> {
-    model_registry::MODELS
-        .iter()
+    model_registry::models()
+        .into_iter()
         .map(|m| ModelStatusEntry {
-            id: m.id.to_string(),
-            name: m.name.to_string(),
-            engine: m.engine,
-            description: m.description.to_string(),
             size_label: model_registry::size_label(m.approx_bytes),
-            ready: model_registry::model_ready(m.id),
-            disk_size: model_registry::model_disk_size(m.id),
-            path: model_registry::model_dir(m.id).to_string_lossy().to_string(),
+            ready: model_registry::model_ready(&m.id),
+            state: model_registry::model_state(&m.id),
+            disk_size: model_registry::model_disk_size(&m.id),
+            path: model_registry::model_dir(&m.id).to_string_lossy().to_string(),
+            runtime: transcriber::runtime_info(&m.id),
+            id: m.id,
+            name: m.name,
+            engine: m.engine,
+            description: m.description,
+            languages: m.languages,
+            capabilities: m.capabilities,
         })
         .collect()
 }
 
+/// Inspect RAM, CPU cores and SIMD support and recommend a default model for
+/// the current transcription language, flagging models likely to run slower
+/// than real time on this machine.
+#[tauri::command]
+pub fn get_model_recommendation(app: tauri::AppHandle) -> hardware::Recommendation {
+    let language = get_transcription_language(app);
+    let language = if language == "auto" { None } else { Some(language) };
+    hardware::recommend(hardware::detect(), language.as_deref())
+}
+
+/// Refresh the model catalog from the configured `modelCatalogSource`
+/// (URL or local file). Returns the active catalog version.
+pub async fn refresh_model_catalog_from_settings(app: &tauri::AppHandle) -> Result<u64, String> {
+    let source = get_model_catalog_source(app.clone());
+    if source.is_empty() {
+        return Ok(model_registry::catalog().version);
+    }
+
+    let client = transcriber::download_client(app).map_err(|e| e.to_string())?;
+    let catalog = model_registry::fetch_catalog(&client, &source)
+        .await
+        .map_err(|e| e.to_string())?;
+    if model_registry::install_catalog(catalog).map_err(|e| e.to_string())? {
+        let _ = app.emit("model-catalog-changed", model_registry::catalog().version);
+    }
+    Ok(model_registry::catalog().version)
+}
+
+#[tauri::command]
+pub async fn refresh_model_catalog(app: tauri::AppHandle) -> Result<u64, String> {
+    refresh_model_catalog_from_settings(&app).await
+}
+
+#[tauri::command]
+pub fn get_model_catalog_source(app: tauri::AppHandle) -> String {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get("modelCatalogSource"))
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_model_catalog_source(app: tauri::AppHandle, source: String) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("modelCatalogSource", serde_json::json!(source.trim()));
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn download_model(app: tauri::AppHandle, model_id: String) -> Result<(), String> {
     transcriber::ensure_model(&app, &model_id).await.map_err(|e| e.to_string())?;
@@ -410,6 +602,107 @@ pub async fn download_model(app: tauri::AppHandle, model_id: String) -> Result<(
     Ok(())
 }
 
+/// Re-hash all files of a model. Files that failed verification are marked
+/// corrupt and re-downloaded by `download_model`; files the catalog publishes
+/// no checksum for are reported as unverified.
+#[tauri::command]
+pub async fn verify_model(
+    app: tauri::AppHandle,
+    model_id: String,
+) -> Result<model_registry::ModelVerification, String> {
+    let mid = model_id.clone();
+    let result = tokio::task::spawn_blocking(move || model_registry::verify_model(&mid))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(
+        "model-verified",
+        serde_json::json!({
+            "modelId": model_id,
+            "badFiles": result.corrupt,
+            "unverifiedFiles": result.unverified,
+        }),
+    );
+    Ok(result)
+}
+
+/// Export an installed model to a single archive for offline machines.
+#[tauri::command]
+pub async fn export_model(model_id: String, path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || model_bundle::export_model(&model_id, std::path::Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Validate and install a model archive created by `export_model`.
+/// Returns the id of the imported model.
+#[tauri::command]
+pub async fn import_model(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    let staged = tokio::task::spawn_blocking(move || model_bundle::stage_bundle(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    transcriber::release_model(staged.model_id()).await;
+    let model_id = tokio::task::spawn_blocking(move || staged.install())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    let _ = app.emit("model-imported", &model_id);
+    Ok(model_id)
+}
+
+#[tauri::command]
+pub fn get_loaded_models() -> Vec<transcriber::LoadedModelInfo> {
+    transcriber::loaded_models()
+}
+
+/// Load a downloaded model into memory ahead of use.
+#[tauri::command]
+pub async fn load_model(
+    app: tauri::AppHandle,
+    model_id: String,
+) -> Result<transcriber::LoadedModelInfo, String> {
+    let _ = app.emit("model-preload-start", &model_id);
+    let mid = model_id.clone();
+    let result = tokio::task::spawn_blocking(move || transcriber::load_model(&mid))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string());
+    let _ = app.emit("model-preload-done", ());
+    let _ = app.emit("model-runtime-changed", &model_id);
+    result
+}
+
+#[tauri::command]
+pub fn get_model_cache_limits() -> transcriber::CacheLimits {
+    transcriber::cache_limits()
+}
+
+/// Bound how many models stay resident (and optionally their total memory).
+/// Least recently used models are unloaded first.
+#[tauri::command]
+pub fn set_model_cache_limits(
+    app: tauri::AppHandle,
+    limits: transcriber::CacheLimits,
+) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("modelCacheLimits", serde_json::json!(limits));
+    transcriber::set_cache_limits(limits);
+    let _ = app.emit("model-runtime-changed", ());
+    Ok(())
+}
+
+/// Free a model's memory while keeping it on disk.
+#[tauri::command]
+pub fn unload_model(app: tauri::AppHandle, model_id: String) -> bool {
+    let unloaded = transcriber::unload_model(&model_id);
+    if unloaded {
+        let _ = app.emit("model-runtime-changed", &model_id);
+    }
+    unloaded
+}
+
 #[tauri::command]
 pub async fn delete_model(model_id: String) -> Result<(), String> {
     transcriber::delete_model(&model_id).await.map_err(|e| e.to_string())
@@ -444,13 +737,16 @@ pub fn get_live_model(app: tauri::AppHandle) -> String {
 
 #[tauri::command]
 pub fn set_live_model(app: tauri::AppHandle, model_id: String) -> Result<(), String> {
+    if model_id != model_registry::AUTO_MODEL_ID && model_registry::find_model(&model_id).is_none() {
+        return Err(format!("Unknown model: {}", model_id));
+    }
     let store = app.store("settings.json").map_err(|e| e.to_string())?;
     store.set("liveModel", serde_json::json!(model_id));
     let _ = app.emit("live-model-changed", &model_id);
 
     // Preload in background so first transcription is instant
     let handle = app.clone();
-    let mid = model_id.clone();
+    let mid = transcriber::preload_target(&model_id);
     let _ = app.emit("model-preload-start", &model_id);
     tauri::async_runtime::spawn(async move {
         tokio::task::spawn_blocking(move || {
@@ -464,6 +760,245 @@ pub fn set_live_model(app: tauri::AppHandle, model_id: String) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_auto_whisper_model(app: tauri::AppHandle) -> String {
+    transcriber::auto_whisper_model(&app)
+}
+
+/// Choose the Whisper model the "auto" live model routes non-English speech to.
+#[tauri::command]
+pub fn set_auto_whisper_model(app: tauri::AppHandle, model_id: String) -> Result<(), String> {
+    match model_registry::find_model(&model_id) {
+        Some(m) if m.engine == model_registry::Engine::Whisper => {}
+        Some(_) => return Err(format!("{} is not a Whisper model", model_id)),
+        None => return Err(format!("Unknown model: {}", model_id)),
+    }
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("autoWhisperModel", serde_json::json!(model_id));
+    let _ = app.emit("auto-whisper-model-changed", &model_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_replacement_rules(app: tauri::AppHandle) -> Vec<postprocess::rules::Rule> {
+    postprocess::rules::load_rules(&app)
+}
+
+#[tauri::command]
+pub fn set_replacement_rules(
+    app: tauri::AppHandle,
+    rules: Vec<postprocess::rules::Rule>,
+) -> Result<Vec<postprocess::rules::Rule>, String> {
+    postprocess::rules::save_rules(&app, rules).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn export_replacement_rules(app: tauri::AppHandle, path: String) -> Result<usize, String> {
+    postprocess::rules::export_rules(&app, std::path::Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// Load rules exported from another install, replacing the current list or
+/// appending to it.
+#[tauri::command]
+pub fn import_replacement_rules(
+    app: tauri::AppHandle,
+    path: String,
+    replace: bool,
+) -> Result<Vec<postprocess::rules::Rule>, String> {
+    postprocess::rules::import_rules(&app, std::path::Path::new(&path), replace)
+        .map_err(|e| e.to_string())
+}
+
+/// Run `text` through the post-processing pipeline, for previewing settings.
+#[tauri::command]
+pub fn preview_post_processing(
+    app: tauri::AppHandle,
+    text: String,
+    language: Option<String>,
+    mode: Option<postprocess::Mode>,
+) -> String {
+    postprocess::process(
+        &app,
+        &text,
+        &postprocess::Context {
+            language,
+            mode: mode.unwrap_or_default(),
+            ..Default::default()
+        },
+    )
+}
+
+#[tauri::command]
+pub fn get_spoken_commands(app: tauri::AppHandle) -> postprocess::punctuation::SpokenCommandSettings {
+    postprocess::punctuation::load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_spoken_commands(
+    app: tauri::AppHandle,
+    settings: postprocess::punctuation::SpokenCommandSettings,
+) -> Result<(), String> {
+    postprocess::punctuation::save_settings(&app, &settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_filler_settings(app: tauri::AppHandle) -> postprocess::fillers::FillerSettings {
+    postprocess::fillers::load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_filler_settings(
+    app: tauri::AppHandle,
+    settings: postprocess::fillers::FillerSettings,
+) -> Result<(), String> {
+    postprocess::fillers::save_settings(&app, &settings).map_err(|e| e.to_string())
+}
+
+/// Built-in filler words for `language`, the starting point for a custom list.
+#[tauri::command]
+pub fn get_default_filler_words(language: String) -> Vec<String> {
+    postprocess::fillers::default_fillers(&language)
+        .iter()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_inverse_text_normalization(app: tauri::AppHandle) -> bool {
+    postprocess::itn::enabled(&app)
+}
+
+#[tauri::command]
+pub fn set_inverse_text_normalization(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    postprocess::itn::set_enabled(&app, enabled).map_err(|e| e.to_string())
+}
+
+/// Languages number normalization knows how to write out.
+#[tauri::command]
+pub fn get_inverse_text_normalization_languages() -> Vec<String> {
+    postprocess::itn::supported_languages()
+        .iter()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_snippets(app: tauri::AppHandle) -> Vec<postprocess::snippets::Snippet> {
+    postprocess::snippets::load_snippets(&app)
+}
+
+/// Store the snippet list, returning it with ids assigned.
+#[tauri::command]
+pub fn set_snippets(
+    app: tauri::AppHandle,
+    snippets: Vec<postprocess::snippets::Snippet>,
+) -> Result<Vec<postprocess::snippets::Snippet>, String> {
+    postprocess::snippets::save_snippets(&app, snippets).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_profiles(app: tauri::AppHandle) -> Vec<profiles::Profile> {
+    profiles::load_profiles(&app)
+}
+
+/// Store the profile list, returning it with ids assigned.
+#[tauri::command]
+pub fn set_profiles(app: tauri::AppHandle, profiles: Vec<profiles::Profile>) -> Result<Vec<profiles::Profile>, String> {
+    profiles::save_profiles(&app, profiles).map_err(|e| e.to_string())
+}
+
+/// The profile that would apply to the app in front right now.
+#[tauri::command]
+pub fn get_frontmost_profile(app: tauri::AppHandle) -> Option<profiles::Profile> {
+    let (app_name, window_title) = frontmost::get_frontmost_app();
+    profiles::resolve(&app, app_name.as_deref(), window_title.as_deref())
+}
+
+#[tauri::command]
+pub fn get_polish_settings(app: tauri::AppHandle) -> polish::PolishSettings {
+    polish::load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_polish_settings(app: tauri::AppHandle, settings: polish::PolishSettings) -> Result<(), String> {
+    polish::save_settings(&app, &settings).map_err(|e| e.to_string())
+}
+
+/// Run `text` through the endpoint with unsaved `settings`, so the settings
+/// page can check a server before enabling the step. Errors are returned
+/// rather than falling back.
+#[tauri::command]
+pub async fn test_polish_endpoint(settings: polish::PolishSettings, text: String) -> Result<String, String> {
+    polish::rewrite(&settings, &text).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_redaction_settings(app: tauri::AppHandle) -> redact::RedactionSettings {
+    redact::load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_redaction_settings(app: tauri::AppHandle, settings: redact::RedactionSettings) -> Result<(), String> {
+    redact::save_settings(&app, &settings).map_err(|e| e.to_string())
+}
+
+/// Redact `text` with unsaved `settings`, for previewing patterns.
+#[tauri::command]
+pub fn preview_redaction(settings: redact::RedactionSettings, text: String) -> Result<String, String> {
+    redact::validate(&settings).map_err(|e| e.to_string())?;
+    Ok(redact::Redactor::new(settings).redact(&text))
+}
+
+/// Built-in spoken commands for `language` (the transcription language when
+/// omitted), for listing in settings.
+#[tauri::command]
+pub fn get_builtin_spoken_commands(
+    app: tauri::AppHandle,
+    language: Option<String>,
+) -> Vec<postprocess::punctuation::SpokenCommand> {
+    let language = language.or_else(|| {
+        Some(get_transcription_language(app)).filter(|l| l != "auto")
+    });
+    postprocess::punctuation::builtin_commands(language.as_deref())
+}
+
+#[tauri::command]
+pub fn get_two_pass_model(app: tauri::AppHandle) -> Option<String> {
+    refine::two_pass_model(&app)
+}
+
+/// Set the model that re-transcribes each recording in the background, or
+/// turn two-pass transcription off with `None`.
+#[tauri::command]
+pub fn set_two_pass_model(app: tauri::AppHandle, model_id: Option<String>) -> Result<(), String> {
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    match &model_id {
+        Some(id) => {
+            if model_registry::find_model(id).is_none() {
+                return Err(format!("Unknown model: {}", id));
+            }
+            store.set("twoPassModel", serde_json::json!(id));
+        }
+        None => {
+            store.delete("twoPassModel");
+        }
+    }
+    let _ = app.emit("two-pass-model-changed", &model_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_last_refined_transcript() -> Option<refine::RefinedTranscript> {
+    refine::last_refined()
+}
+
+/// Paste (or copy) the most recent refined transcript in place of the draft.
+/// The app profile the draft was made with decides how, if it says.
+#[tauri::command]
+pub fn apply_refined_transcript(app: tauri::AppHandle, auto_paste: bool) -> Result<bool, String> {
+    refine::apply_last_refined(&app, paster::paste_mode(&app, auto_paste)).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_transcription_language(app: tauri::AppHandle) -> String {
     app.store("settings.json")
@@ -481,6 +1016,47 @@ pub fn set_transcription_language(app: tauri::AppHandle, language: String) -> Re
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_clipboard_restore(app: tauri::AppHandle) -> paster::ClipboardRestore {
+    paster::load_restore_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_clipboard_restore(app: tauri::AppHandle, settings: paster::ClipboardRestore) -> Result<(), String> {
+    paster::save_restore_settings(&app, &settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_paste_chord(app: tauri::AppHandle) -> paster::PasteChord {
+    paster::stored_paste_chord(&app)
+}
+
+#[tauri::command]
+pub fn set_paste_chord(app: tauri::AppHandle, chord: paster::PasteChord) -> Result<(), String> {
+    paster::save_paste_chord(&app, chord).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_paste_mode(app: tauri::AppHandle, mode: paster::PasteMode) -> Result<(), String> {
+    paster::save_paste_mode(&app, mode).map_err(|e| e.to_string())
+}
+
+/// Whether the "type" paste mode works on this platform.
+#[tauri::command]
+pub fn is_typing_supported() -> bool {
+    typer::supported()
+}
+
+#[tauri::command]
+pub fn get_typing_settings(app: tauri::AppHandle) -> typer::TypingSettings {
+    typer::load_settings(&app)
+}
+
+#[tauri::command]
+pub fn set_typing_settings(app: tauri::AppHandle, settings: typer::TypingSettings) -> Result<(), String> {
+    typer::save_settings(&app, &settings).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_translate_to_english(app: tauri::AppHandle) -> bool {
     app.store("settings.json")
@@ -498,11 +1074,123 @@ pub fn set_translate_to_english(app: tauri::AppHandle, enabled: bool) -> Result<
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_models_dir() -> String {
+    model_registry::models_base_dir().to_string_lossy().to_string()
+}
+
+/// Point model storage at `path` (or back to the default when `None`),
+/// optionally moving already downloaded models along.
+#[tauri::command]
+pub async fn set_models_dir(
+    app: tauri::AppHandle,
+    path: Option<String>,
+    migrate: bool,
+) -> Result<Vec<String>, String> {
+    if transcriber::is_downloading() {
+        return Err("Cannot change the models folder while a download is in progress".to_string());
+    }
+
+    let new_dir = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(std::path::PathBuf::from);
+    if new_dir.as_ref().is_some_and(|dir| !dir.is_absolute()) {
+        return Err("The models folder must be an absolute path".to_string());
+    }
+    let target = new_dir.clone().unwrap_or_else(model_registry::default_models_base_dir);
+    let current = model_registry::models_base_dir();
+
+    let moved = if migrate {
+        let (from, to) = (current.clone(), target.clone());
+        tokio::task::spawn_blocking(move || model_registry::migrate_models(&from, &to))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?
+    } else {
+        std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+        Vec::new()
+    };
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    match &new_dir {
+        Some(dir) => store.set("modelsDir", serde_json::json!(dir.to_string_lossy())),
+        None => {
+            store.delete("modelsDir");
+        }
+    }
+    model_registry::set_models_base_dir(new_dir);
+    let _ = app.emit("models-dir-changed", target.to_string_lossy().to_string());
+    Ok(moved)
+}
+
+#[tauri::command]
+pub fn get_download_settings(app: tauri::AppHandle) -> serde_json::Value {
+    let settings = transcriber::download_settings(&app);
+    serde_json::json!({
+        "mirrorUrl": settings.mirror,
+        "proxy": settings.proxy,
+    })
+}
+
+#[tauri::command]
+pub fn set_download_settings(
+    app: tauri::AppHandle,
+    mirror_url: Option<String>,
+    proxy: Option<String>,
+) -> Result<(), String> {
+    let proxy = proxy.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(p) = &proxy {
+        reqwest::Proxy::all(p).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    }
+    let mirror_url = mirror_url.map(|m| m.trim().to_string()).filter(|m| !m.is_empty());
+    if let Some(m) = &mirror_url {
+        if !m.starts_with("http://") && !m.starts_with("https://") {
+            return Err(format!("Invalid mirror URL: {}", m));
+        }
+    }
+
+    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+    store.set("modelMirrorUrl", serde_json::json!(mirror_url.unwrap_or_default()));
+    store.set("downloadProxy", serde_json::json!(proxy.unwrap_or_default()));
+    Ok(())
+}
+
+/// Disk usage of models and recordings, plus orphaned model directories.
+#[tauri::command]
+pub async fn get_storage_usage() -> Result<storage::StorageReport, String> {
+    tokio::task::spawn_blocking(storage::storage_report)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_orphaned_models() -> Result<Vec<String>, String> {
+    if transcriber::is_downloading() {
+        return Err("Cannot clean up the models folder while a download is in progress".to_string());
+    }
+    tokio::task::spawn_blocking(storage::remove_orphaned_models)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn is_download_in_progress() -> bool {
     transcriber::is_downloading()
 }
 
+#[tauri::command]
+pub fn get_download_status() -> Vec<transcriber::DownloadStatus> {
+    transcriber::download_status()
+}
+
+#[tauri::command]
+pub fn cancel_model_download(model_id: String) -> bool {
+    transcriber::cancel_download(&model_id)
+}
+
 #[tauri::command]
 pub fn complete_onboarding(app: tauri::AppHandle) {
     if let Ok(store) = app.store("settings.json") {
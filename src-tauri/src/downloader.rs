@@ -0,0 +1,368 @@
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Build the HTTP client used for downloads, routed through `proxy`
+/// (e.g. `http://proxy.corp:3128`) when set.
+pub fn build_client(proxy: Option<&str>) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = proxy {
+        let proxy = reqwest::Proxy::all(proxy).with_context(|| format!("Invalid proxy URL: {}", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+    builder.build().context("Failed to create HTTP client")
+}
+
+/// Rewrite `url` to be served from `mirror` by swapping the scheme and host:
+/// `https://huggingface.co/org/repo/resolve/main/f.bin` with mirror
+/// `https://mirror.corp/hf` becomes `https://mirror.corp/hf/org/repo/resolve/main/f.bin`.
+pub fn mirror_url(url: &str, mirror: &str) -> String {
+    let path = url
+        .split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|i| &rest[i..]))
+        .unwrap_or("/");
+    format!("{}{}", mirror.trim_end_matches('/'), path)
+}
+
+/// Error returned when a download is stopped through its cancel flag.
+/// The part file is kept; the caller decides whether to discard it.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Download cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Path of the in-progress download for `dest` (`<dest>.part`).
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Download `url` to `dest`, resuming from `<dest>.part` when the server
+/// supports range requests. Transient failures are retried with exponential
+/// backoff; the part file is only renamed to `dest` once the body is complete.
+///
+/// `on_progress` is called with `(bytes_on_disk, total_bytes)` after every chunk,
+/// where `total_bytes` is 0 if the server didn't report a length. Setting
+/// `cancel` stops the download with a [`Cancelled`] error.
+/// Returns the number of bytes fetched over the network by this call.
+pub async fn download_file<F>(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> Result<u64>
+where
+    F: FnMut(u64, u64),
+{
+    let part = part_path(dest);
+    let mut fetched: u64 = 0;
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match try_download(client, url, &part, cancel, &mut fetched, &mut on_progress).await {
+            Ok(()) => break,
+            Err(e) if e.retryable && attempt < MAX_ATTEMPTS => {
+                eprintln!(
+                    "[audioshift] Download of {} failed (attempt {}/{}): {}, retrying in {:?}",
+                    url, attempt, MAX_ATTEMPTS, e.error, backoff
+                );
+                tokio::time::sleep(backoff).await;
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(Cancelled.into());
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                attempt += 1;
+            }
+            Err(e) => return Err(e.error),
+        }
+    }
+
+    tokio::fs::rename(&part, dest)
+        .await
+        .with_context(|| format!("Failed to move {:?} into place", part))?;
+    Ok(fetched)
+}
+
+struct AttemptError {
+    error: anyhow::Error,
+    retryable: bool,
+}
+
+impl AttemptError {
+    fn retryable(error: impl Into<anyhow::Error>) -> Self {
+        Self { error: error.into(), retryable: true }
+    }
+
+    fn fatal(error: impl Into<anyhow::Error>) -> Self {
+        Self { error: error.into(), retryable: false }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Total length from a `Content-Range: bytes <start>-<end>/<total>` header.
+fn content_range_total(resp: &reqwest::Response) -> Option<u64> {
+    resp.headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+async fn try_download<F>(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    cancel: &AtomicBool,
+    fetched: &mut u64,
+    on_progress: &mut F,
+) -> std::result::Result<(), AttemptError>
+where
+    F: FnMut(u64, u64),
+{
+    let existing = tokio::fs::metadata(part).await.map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing));
+    }
+    let resp = request.send().await.map_err(AttemptError::retryable)?;
+    let status = resp.status();
+
+    let (mut file, mut on_disk, total) = match status {
+        StatusCode::PARTIAL_CONTENT if existing > 0 => {
+            let total = content_range_total(&resp).unwrap_or(0);
+            let file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(part)
+                .await
+                .map_err(AttemptError::fatal)?;
+            (file, existing, total)
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE if existing > 0 => {
+            // The part file already covers the whole body if its length matches
+            // the reported total; otherwise it's stale and we start over.
+            if content_range_total(&resp) == Some(existing) {
+                on_progress(existing, existing);
+                return Ok(());
+            }
+            let _ = tokio::fs::remove_file(part).await;
+            return Err(AttemptError::retryable(anyhow::anyhow!(
+                "Server rejected resume at byte {}",
+                existing
+            )));
+        }
+        s if s.is_success() => {
+            // Server ignored the Range header (or this is a fresh download):
+            // the body starts at byte zero.
+            let total = resp.content_length().unwrap_or(0);
+            let file = tokio::fs::File::create(part).await.map_err(AttemptError::fatal)?;
+            (file, 0, total)
+        }
+        s => {
+            let err = anyhow::anyhow!("HTTP {} for {}", s, url);
+            return Err(if is_retryable_status(s) {
+                AttemptError::retryable(err)
+            } else {
+                AttemptError::fatal(err)
+            });
+        }
+    };
+
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        if cancel.load(Ordering::Relaxed) {
+            let _ = file.flush().await;
+            return Err(AttemptError::fatal(Cancelled));
+        }
+        let chunk = chunk.map_err(AttemptError::retryable)?;
+        file.write_all(&chunk).await.map_err(AttemptError::fatal)?;
+        on_disk += chunk.len() as u64;
+        *fetched += chunk.len() as u64;
+        on_progress(on_disk, total);
+    }
+    file.flush().await.map_err(AttemptError::fatal)?;
+    file.sync_all().await.map_err(AttemptError::fatal)?;
+
+    if total > 0 && on_disk < total {
+        return Err(AttemptError::retryable(anyhow::anyhow!(
+            "Connection closed after {} of {} bytes",
+            on_disk,
+            total
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    const BODY: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    /// What the stand-in server does with one request: the response head and
+    /// body to write before closing the connection.
+    type Handler = Box<dyn Fn(Option<u64>) -> (String, Vec<u8>) + Send>;
+
+    /// Serve one connection per handler on a loopback port. Returns the URL
+    /// and the `Range` start (if any) of each request received.
+    fn serve(handlers: Vec<Handler>) -> (String, Arc<Mutex<Vec<Option<u64>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+        std::thread::spawn(move || {
+            for handler in handlers {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut range = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                        range = value.trim().trim_end_matches('-').parse().ok();
+                    }
+                }
+                seen.lock().unwrap().push(range);
+                let (head, body) = handler(range);
+                let _ = stream.write_all(head.as_bytes());
+                let _ = stream.write_all(&body);
+            }
+        });
+        (url, ranges)
+    }
+
+    fn ok(body: &[u8]) -> (String, Vec<u8>) {
+        let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+        (head, body.to_vec())
+    }
+
+    fn partial(start: u64) -> (String, Vec<u8>) {
+        let rest = &BODY[start as usize..];
+        let head = format!(
+            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+            rest.len(),
+            start,
+            BODY.len() - 1,
+            BODY.len()
+        );
+        (head, rest.to_vec())
+    }
+
+    fn not_satisfiable(total: usize) -> (String, Vec<u8>) {
+        let head = format!(
+            "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            total
+        );
+        (head, Vec::new())
+    }
+
+    fn temp_dest(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("audioshift-dl-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("model.bin")
+    }
+
+    fn run(url: &str, dest: &Path) -> Result<u64> {
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(download_file(&client, url, dest, &AtomicBool::new(false), |_, _| {}))
+    }
+
+    #[test]
+    fn resumes_from_part_file() {
+        let dest = temp_dest("resume");
+        std::fs::write(part_path(&dest), &BODY[..10]).unwrap();
+        let (url, ranges) = serve(vec![Box::new(|range| partial(range.unwrap_or(0)))]);
+
+        let fetched = run(&url, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        assert_eq!(fetched, (BODY.len() - 10) as u64);
+        assert_eq!(*ranges.lock().unwrap(), vec![Some(10)]);
+        assert!(!part_path(&dest).exists());
+    }
+
+    #[test]
+    fn restarts_when_server_ignores_range() {
+        let dest = temp_dest("ignore-range");
+        std::fs::write(part_path(&dest), b"stale bytes").unwrap();
+        let (url, ranges) = serve(vec![Box::new(|_| ok(BODY))]);
+
+        let fetched = run(&url, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        assert_eq!(fetched, BODY.len() as u64);
+        assert_eq!(*ranges.lock().unwrap(), vec![Some(11)]);
+    }
+
+    #[test]
+    fn retries_after_dropped_connection() {
+        let dest = temp_dest("dropped");
+        let (url, ranges) = serve(vec![
+            // Promise the whole body, then hang up halfway through
+            Box::new(|_| {
+                let (head, _) = ok(BODY);
+                (head, BODY[..20].to_vec())
+            }),
+            Box::new(|range| partial(range.unwrap_or(0))),
+        ]);
+
+        run(&url, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        assert_eq!(*ranges.lock().unwrap(), vec![None, Some(20)]);
+    }
+
+    #[test]
+    fn complete_part_file_is_accepted_on_416() {
+        let dest = temp_dest("416-complete");
+        std::fs::write(part_path(&dest), BODY).unwrap();
+        let (url, _) = serve(vec![Box::new(|_| not_satisfiable(BODY.len()))]);
+
+        assert_eq!(run(&url, &dest).unwrap(), 0);
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+    }
+
+    #[test]
+    fn stale_part_file_is_discarded_on_416() {
+        let dest = temp_dest("416-stale");
+        std::fs::write(part_path(&dest), [b'x'; 50]).unwrap();
+        let (url, ranges) = serve(vec![
+            Box::new(|_| not_satisfiable(BODY.len())),
+            Box::new(|_| ok(BODY)),
+        ]);
+
+        run(&url, &dest).unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        assert_eq!(*ranges.lock().unwrap(), vec![Some(50), None]);
+    }
+}
@@ -4,6 +4,10 @@ use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 
+use crate::polish::Polish;
+use crate::refine::Refinement;
+use crate::transcriber::RouteDecision;
+
 const SAMPLE_RATE: u32 = 16000;
 const BITS_PER_SAMPLE: u16 = 16;
 const NUM_CHANNELS: u16 = 1;
@@ -11,7 +15,11 @@ const NUM_CHANNELS: u16 = 1;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingMeta {
     pub id: String,
+    /// Text after post-processing, as it was pasted.
     pub text: String,
+    /// Transcript before post-processing, when that changed it.
+    #[serde(default)]
+    pub raw_text: Option<String>,
     pub timestamp: i64,
     pub app_name: Option<String>,
     pub window_title: Option<String>,
@@ -22,6 +30,18 @@ pub struct RecordingMeta {
     pub language: Option<String>,
     pub translate: bool,
     pub app_version: String,
+    /// Set when the "auto" live model picked `model_id`.
+    #[serde(default)]
+    pub route: Option<RouteDecision>,
+    /// Name of the app profile the dictation used.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Set when a local LLM rewrote the text; holds the version before it.
+    #[serde(default)]
+    pub polish: Option<Polish>,
+    /// Set once a second pass replaced the draft `text`.
+    #[serde(default)]
+    pub refinement: Option<Refinement>,
 }
 
 pub fn recordings_dir() -> PathBuf {
@@ -48,6 +68,17 @@ pub fn save_recording(samples: &[f32], meta: &RecordingMeta) -> Result<PathBuf>
     Ok(dir)
 }
 
+/// Rewrite a recording's meta.json in place.
+pub fn update_meta(id: &str, update: impl FnOnce(&mut RecordingMeta)) -> Result<RecordingMeta> {
+    let meta_path = recordings_dir().join(id).join("meta.json");
+    let data = fs::read_to_string(&meta_path).context("Failed to read meta.json")?;
+    let mut meta: RecordingMeta = serde_json::from_str(&data).context("Failed to parse meta.json")?;
+    update(&mut meta);
+    let json = serde_json::to_string_pretty(&meta).context("Failed to serialize meta")?;
+    fs::write(&meta_path, json).context("Failed to write meta.json")?;
+    Ok(meta)
+}
+
 pub fn load_all_recordings() -> Result<Vec<RecordingMeta>> {
     let base = recordings_dir();
     if !base.exists() {
@@ -0,0 +1,277 @@
+use serde::Serialize;
+
+use crate::model_registry::{self, Engine, ModelDef};
+use crate::transcriber;
+
+/// CPU core count the catalog's `realtimeFactor` figures were measured with.
+const REFERENCE_CORES: f64 = 8.0;
+/// Slowdown applied when the CPU lacks AVX2/NEON SIMD.
+const NO_SIMD_PENALTY: f64 = 4.0;
+/// Speedup applied to GPU-capable models when a GPU backend is available.
+const GPU_SPEEDUP: f64 = 3.0;
+/// Recommended models must leave this much headroom below real time.
+const COMFORTABLE_RTF: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareInfo {
+    pub total_ram: Option<u64>,
+    pub available_ram: Option<u64>,
+    pub physical_cores: usize,
+    pub logical_cores: usize,
+    pub arch: &'static str,
+    /// Detected SIMD extensions relevant to inference (e.g. `avx2`, `neon`).
+    pub cpu_features: Vec<&'static str>,
+    /// Engines whose builds can offload to a GPU here.
+    pub gpu_engines: Vec<Engine>,
+}
+
+pub fn detect() -> HardwareInfo {
+    let (total_ram, available_ram) = memory();
+    HardwareInfo {
+        total_ram,
+        available_ram,
+        physical_cores: num_cpus::get_physical(),
+        logical_cores: num_cpus::get(),
+        arch: std::env::consts::ARCH,
+        cpu_features: cpu_features(),
+        gpu_engines: [Engine::Parakeet, Engine::Whisper]
+            .into_iter()
+            .filter(|e| transcriber::gpu_backend(*e).is_some())
+            .collect(),
+    }
+}
+
+fn cpu_features() -> Vec<&'static str> {
+    #[allow(unused_mut)]
+    let mut features = Vec::new();
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        for (name, present) in [
+            ("sse4.1", std::arch::is_x86_feature_detected!("sse4.1")),
+            ("avx", std::arch::is_x86_feature_detected!("avx")),
+            ("avx2", std::arch::is_x86_feature_detected!("avx2")),
+            ("fma", std::arch::is_x86_feature_detected!("fma")),
+            ("f16c", std::arch::is_x86_feature_detected!("f16c")),
+            ("avx512f", std::arch::is_x86_feature_detected!("avx512f")),
+        ] {
+            if present {
+                features.push(name);
+            }
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        for (name, present) in [
+            ("neon", std::arch::is_aarch64_feature_detected!("neon")),
+            ("fp16", std::arch::is_aarch64_feature_detected!("fp16")),
+            ("dotprod", std::arch::is_aarch64_feature_detected!("dotprod")),
+        ] {
+            if present {
+                features.push(name);
+            }
+        }
+    }
+    features
+}
+
+/// (total, available) physical memory in bytes.
+fn memory() -> (Option<u64>, Option<u64>) {
+    #[cfg(target_os = "macos")]
+    {
+        let mut size: u64 = 0;
+        let mut len = std::mem::size_of::<u64>();
+        let rc = unsafe {
+            libc::sysctlbyname(
+                c"hw.memsize".as_ptr(),
+                &mut size as *mut u64 as *mut libc::c_void,
+                &mut len,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        ((rc == 0).then_some(size), None)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+        let field = |key: &str| {
+            meminfo
+                .lines()
+                .find(|l| l.starts_with(key))
+                .and_then(|l| l.split_whitespace().nth(1))
+                .and_then(|kb| kb.parse::<u64>().ok())
+                .map(|kb| kb * 1024)
+        };
+        (field("MemTotal:"), field("MemAvailable:"))
+    }
+
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+        let mut status = MEMORYSTATUSEX {
+            dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+            ..Default::default()
+        };
+        match unsafe { GlobalMemoryStatusEx(&mut status) } {
+            Ok(()) => (Some(status.ullTotalPhys), Some(status.ullAvailPhys)),
+            Err(_) => (None, None),
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    {
+        (None, None)
+    }
+}
+
+/// Resident memory of this process in bytes.
+pub fn process_resident_bytes() -> Option<u64> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+        let rc = unsafe {
+            libc::proc_pidinfo(
+                std::process::id() as libc::c_int,
+                libc::PROC_PIDTASKINFO,
+                0,
+                &mut info as *mut libc::proc_taskinfo as *mut libc::c_void,
+                size,
+            )
+        };
+        (rc == size).then_some(info.pti_resident_size)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // Second field of statm is resident pages
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        Some(pages * page_size.max(0) as u64)
+    }
+
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+        use windows::Win32::System::Threading::GetCurrentProcess;
+
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) }.ok()?;
+        Some(counters.WorkingSetSize as u64)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelAssessment {
+    pub id: String,
+    pub name: String,
+    /// Estimated processing time divided by audio duration on this machine.
+    pub estimated_realtime_factor: Option<f64>,
+    pub fits_in_memory: bool,
+    /// Likely to take longer to transcribe than the audio lasts.
+    pub slower_than_realtime: bool,
+    pub supports_language: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub hardware: HardwareInfo,
+    pub recommended_model_id: Option<String>,
+    pub models: Vec<ModelAssessment>,
+}
+
+fn estimate_rtf(hw: &HardwareInfo, model: &ModelDef) -> Option<f64> {
+    let perf = model.performance.as_ref()?;
+    // Inference threads are capped at 8 (see transcribe_whisper)
+    let cores = hw.physical_cores.clamp(1, 8) as f64;
+    let mut rtf = perf.realtime_factor * REFERENCE_CORES / cores;
+    let has_simd = hw.cpu_features.iter().any(|f| *f == "avx2" || *f == "neon");
+    if !has_simd {
+        rtf *= NO_SIMD_PENALTY;
+    }
+    if model.capabilities.gpu && hw.gpu_engines.contains(&model.engine) {
+        rtf /= GPU_SPEEDUP;
+    }
+    Some(rtf)
+}
+
+fn supports_language(model: &ModelDef, language: Option<&str>) -> bool {
+    match language {
+        None => true,
+        Some(lang) => model_registry::supports_language(model, lang),
+    }
+}
+
+/// Assess every catalog model against `hw` and pick a default: the most
+/// accurate model that fits in memory, supports `language` and runs
+/// comfortably faster than real time.
+pub fn recommend(hw: HardwareInfo, language: Option<&str>) -> Recommendation {
+    let catalog = model_registry::catalog();
+    let ram = hw.available_ram.or(hw.total_ram);
+
+    let models: Vec<ModelAssessment> = catalog
+        .models
+        .iter()
+        .map(|m| {
+            let rtf = estimate_rtf(&hw, m);
+            let fits = match (ram, &m.performance) {
+                (Some(ram), Some(perf)) => perf.ram_bytes <= ram,
+                _ => true,
+            };
+            ModelAssessment {
+                id: m.id.clone(),
+                name: m.name.clone(),
+                estimated_realtime_factor: rtf,
+                fits_in_memory: fits,
+                slower_than_realtime: rtf.is_some_and(|r| r >= 1.0),
+                supports_language: supports_language(m, language),
+            }
+        })
+        .collect();
+
+    let accuracy = |id: &str| {
+        catalog
+            .models
+            .iter()
+            .find(|m| m.id == id)
+            .and_then(|m| m.performance.as_ref())
+            .map_or(0, |p| p.accuracy)
+    };
+    let candidates = || {
+        models
+            .iter()
+            .filter(|a| a.fits_in_memory && a.supports_language)
+    };
+    let recommended = candidates()
+        .filter(|a| a.estimated_realtime_factor.is_some_and(|r| r <= COMFORTABLE_RTF))
+        .max_by_key(|a| accuracy(&a.id))
+        .or_else(|| {
+            // Nothing is comfortably fast: fall back to the fastest usable model
+            candidates()
+                .filter(|a| a.estimated_realtime_factor.is_some())
+                .min_by(|a, b| {
+                    a.estimated_realtime_factor
+                        .partial_cmp(&b.estimated_realtime_factor)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        })
+        .map(|a| a.id.clone());
+
+    Recommendation {
+        hardware: hw,
+        recommended_model_id: recommended,
+        models,
+    }
+}
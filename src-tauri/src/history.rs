@@ -2,11 +2,16 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 
 use crate::file_storage;
+use crate::polish::Polish;
+use crate::redact;
+use crate::refine::Refinement;
+use crate::transcriber::RouteDecision;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
     pub id: String,
     pub text: String,
+    pub raw_text: Option<String>,
     pub timestamp: i64,
     pub app_name: Option<String>,
     pub window_title: Option<String>,
@@ -18,11 +23,16 @@ pub struct HistoryEntry {
     pub language: Option<String>,
     pub translate: bool,
     pub app_version: String,
+    pub route: Option<RouteDecision>,
+    pub profile: Option<String>,
+    pub polish: Option<Polish>,
+    pub refinement: Option<Refinement>,
 }
 
 pub struct RecordingInfo {
     pub samples: Vec<f32>,
     pub text: String,
+    pub raw_text: String,
     pub app_name: Option<String>,
     pub window_title: Option<String>,
     pub duration_ms: u64,
@@ -30,17 +40,22 @@ pub struct RecordingInfo {
     pub model_id: String,
     pub language: Option<String>,
     pub translate: bool,
+    pub route: Option<RouteDecision>,
+    pub profile: Option<String>,
+    pub polish: Option<Polish>,
 }
 
-pub fn add_entry(app: &AppHandle, info: RecordingInfo) {
+/// Save a recording and return its id, or `None` if it couldn't be written.
+pub fn add_entry(app: &AppHandle, info: RecordingInfo) -> Option<String> {
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default();
     let id = timestamp.as_secs().to_string();
     let char_count = info.text.chars().count();
 
-    let meta = file_storage::RecordingMeta {
+    let mut meta = file_storage::RecordingMeta {
         id: id.clone(),
+        raw_text: (info.raw_text != info.text).then_some(info.raw_text),
         text: info.text,
         timestamp: timestamp.as_millis() as i64,
         app_name: info.app_name,
@@ -52,16 +67,25 @@ pub fn add_entry(app: &AppHandle, info: RecordingInfo) {
         language: info.language,
         translate: info.translate,
         app_version: env!("CARGO_PKG_VERSION").to_string(),
+        route: info.route,
+        profile: info.profile,
+        polish: info.polish,
+        refinement: None,
     };
+    if let Some(redactor) = redact::for_history(app) {
+        redactor.redact_meta(&mut meta);
+    }
 
-    match file_storage::save_recording(&info.samples, &meta) {
-        Ok(_dir) => {}
+    let saved = match file_storage::save_recording(&info.samples, &meta) {
+        Ok(_dir) => Some(id),
         Err(e) => {
             eprintln!("[audioshift] Failed to save recording: {e}");
+            None
         }
-    }
+    };
 
     let _ = app.emit("history-updated", ());
+    saved
 }
 
 pub fn get_entries(_app: &AppHandle) -> Result<Vec<HistoryEntry>, String> {
@@ -74,6 +98,7 @@ pub fn get_entries(_app: &AppHandle) -> Result<Vec<HistoryEntry>, String> {
             HistoryEntry {
                 id: meta.id,
                 text: meta.text,
+                raw_text: meta.raw_text,
                 timestamp: meta.timestamp,
                 app_name: meta.app_name,
                 window_title: meta.window_title,
@@ -85,6 +110,10 @@ pub fn get_entries(_app: &AppHandle) -> Result<Vec<HistoryEntry>, String> {
                 language: meta.language,
                 translate: meta.translate,
                 app_version: meta.app_version,
+                route: meta.route,
+                profile: meta.profile,
+                polish: meta.polish,
+                refinement: meta.refinement,
             }
         })
         .collect();
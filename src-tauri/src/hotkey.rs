@@ -1,8 +1,17 @@
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
 use tauri::{App, AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_store::StoreExt;
 
+use crate::postprocess::Mode;
 use crate::state::{AppState, Status};
 
+const MODE_HOTKEYS_KEY: &str = "modeHotkeys";
+
+/// Registered shortcuts that start dictation in a specific mode.
+static MODE_SHORTCUTS: Mutex<Vec<(Shortcut, Mode)>> = Mutex::new(Vec::new());
+
 #[cfg(target_os = "macos")]
 pub const DEFAULT_HOTKEY: &str = "Alt+Space";
 
@@ -13,7 +22,7 @@ pub fn default_hotkey() -> &'static str {
     DEFAULT_HOTKEY
 }
 
-fn shortcut_handler(app: &AppHandle, _shortcut: &Shortcut, event: tauri_plugin_global_shortcut::ShortcutEvent) {
+fn shortcut_handler(app: &AppHandle, shortcut: &Shortcut, event: tauri_plugin_global_shortcut::ShortcutEvent) {
     if event.state != ShortcutState::Pressed {
         return;
     }
@@ -23,6 +32,12 @@ fn shortcut_handler(app: &AppHandle, _shortcut: &Shortcut, event: tauri_plugin_g
 
     match current {
         Status::Idle => {
+            let mode = MODE_SHORTCUTS
+                .lock()
+                .iter()
+                .find(|(s, _)| s == shortcut)
+                .map(|(_, mode)| *mode);
+            state.set_dictation_mode(mode);
             let _ = app.emit("recording-toggle", "start");
         }
         Status::Recording => {
@@ -39,6 +54,7 @@ pub fn register_default_hotkey(app: &App) -> Result<(), Box<dyn std::error::Erro
     // Unregister first to avoid "already registered" errors.
     let _ = gs.unregister(shortcut);
     gs.on_shortcut(shortcut, shortcut_handler)?;
+    register_mode_hotkeys(app.handle(), shortcut);
     Ok(())
 }
 
@@ -51,6 +67,56 @@ pub fn update_hotkey(app: &AppHandle, new_shortcut: &str) -> Result<(), Box<dyn
     // Register the new one
     let shortcut: Shortcut = new_shortcut.parse()?;
     gs.on_shortcut(shortcut, shortcut_handler)?;
+    register_mode_hotkeys(app, shortcut);
+
+    Ok(())
+}
+
+/// Stored mode shortcuts (`modeHotkeys`), e.g. `{"code": "Ctrl+Alt+Space"}`.
+pub fn mode_hotkeys(app: &AppHandle) -> BTreeMap<Mode, String> {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(MODE_HOTKEYS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
 
+/// Validate, store and register the mode shortcuts.
+pub fn set_mode_hotkeys(app: &AppHandle, hotkeys: &BTreeMap<Mode, String>) -> Result<(), Box<dyn std::error::Error>> {
+    for shortcut in hotkeys.values() {
+        shortcut.parse::<Shortcut>()?;
+    }
+    let store = app.store("settings.json")?;
+    store.set(MODE_HOTKEYS_KEY, serde_json::to_value(hotkeys)?);
+    if let Ok(main) = app.state::<AppState>().hotkey().parse() {
+        register_mode_hotkeys(app, main);
+    }
     Ok(())
 }
+
+/// (Re)register the mode shortcuts alongside the main hotkey. One that
+/// can't be registered is logged and skipped.
+fn register_mode_hotkeys(app: &AppHandle, main: Shortcut) {
+    let gs = app.global_shortcut();
+    let mut registered = MODE_SHORTCUTS.lock();
+    for (shortcut, _) in registered.drain(..) {
+        let _ = gs.unregister(shortcut);
+    }
+    for (mode, key) in mode_hotkeys(app) {
+        let shortcut: Shortcut = match key.parse() {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("[audioshift] Invalid {:?} hotkey {}: {}", mode, key, e);
+                continue;
+            }
+        };
+        if shortcut == main {
+            continue;
+        }
+        let _ = gs.unregister(shortcut);
+        match gs.on_shortcut(shortcut, shortcut_handler) {
+            Ok(()) => registered.push((shortcut, mode)),
+            Err(e) => eprintln!("[audioshift] Failed to register {:?} hotkey {}: {}", mode, key, e),
+        }
+    }
+}
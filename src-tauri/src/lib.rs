@@ -1,20 +1,33 @@
 mod commands;
+mod downloader;
 #[cfg(target_os = "macos")]
 mod dock_menu;
 mod escape_monitor;
 mod file_storage;
 mod frontmost;
+mod hardware;
 mod history;
 mod hotkey;
+#[cfg(target_os = "linux")]
+mod linux_input;
 mod login_item;
+mod model_bundle;
 mod model_registry;
 mod paster;
 mod plugins;
+mod polish;
+mod postprocess;
+mod profiles;
 mod recorder;
+mod redact;
+mod refine;
+mod settings_list;
 mod state;
+mod storage;
 mod transcriber;
 mod tray;
 mod tray_icons;
+mod typer;
 mod updater;
 mod windows;
 
@@ -62,6 +75,8 @@ pub fn run() {
             commands::cancel_recording,
             commands::get_current_hotkey,
             commands::set_hotkey,
+            commands::get_mode_hotkeys,
+            commands::set_mode_hotkey,
             commands::check_microphone_permission,
             commands::request_microphone_permission,
             commands::check_accessibility_permission,
@@ -73,18 +88,77 @@ pub fn run() {
             commands::delete_history_entry,
             commands::clear_history,
             commands::get_all_models_status,
+            commands::get_model_recommendation,
+            commands::refresh_model_catalog,
+            commands::get_model_catalog_source,
+            commands::set_model_catalog_source,
             commands::download_model,
+            commands::verify_model,
+            commands::export_model,
+            commands::import_model,
             commands::delete_model,
+            commands::get_loaded_models,
+            commands::load_model,
+            commands::unload_model,
+            commands::get_model_cache_limits,
+            commands::set_model_cache_limits,
             commands::check_onboarding_needed,
             commands::complete_onboarding,
             commands::show_onboarding,
             commands::get_live_model,
             commands::set_live_model,
+            commands::get_auto_whisper_model,
+            commands::set_auto_whisper_model,
+            commands::get_replacement_rules,
+            commands::set_replacement_rules,
+            commands::export_replacement_rules,
+            commands::import_replacement_rules,
+            commands::preview_post_processing,
+            commands::get_spoken_commands,
+            commands::set_spoken_commands,
+            commands::get_builtin_spoken_commands,
+            commands::get_filler_settings,
+            commands::set_filler_settings,
+            commands::get_default_filler_words,
+            commands::get_inverse_text_normalization,
+            commands::set_inverse_text_normalization,
+            commands::get_inverse_text_normalization_languages,
+            commands::get_snippets,
+            commands::set_snippets,
+            commands::get_profiles,
+            commands::set_profiles,
+            commands::get_frontmost_profile,
+            commands::get_polish_settings,
+            commands::set_polish_settings,
+            commands::test_polish_endpoint,
+            commands::get_redaction_settings,
+            commands::set_redaction_settings,
+            commands::preview_redaction,
+            commands::get_two_pass_model,
+            commands::set_two_pass_model,
+            commands::get_last_refined_transcript,
+            commands::apply_refined_transcript,
             commands::get_transcription_language,
             commands::set_transcription_language,
             commands::get_translate_to_english,
             commands::set_translate_to_english,
+            commands::get_clipboard_restore,
+            commands::set_clipboard_restore,
+            commands::get_paste_chord,
+            commands::set_paste_chord,
+            commands::set_paste_mode,
+            commands::is_typing_supported,
+            commands::get_typing_settings,
+            commands::set_typing_settings,
+            commands::get_models_dir,
+            commands::set_models_dir,
+            commands::get_download_settings,
+            commands::set_download_settings,
+            commands::get_storage_usage,
+            commands::remove_orphaned_models,
             commands::is_download_in_progress,
+            commands::get_download_status,
+            commands::cancel_model_download,
             commands::restart_app,
             commands::set_overlay_corner_radius,
             commands::get_build_variant,
@@ -98,6 +172,27 @@ pub fn run() {
             login_item::mas_login_item_disable,
         ])
         .setup(|app| {
+            // Use the custom models folder, if one was chosen
+            if let Some(dir) = app
+                .store("settings.json")
+                .ok()
+                .and_then(|s| s.get("modelsDir"))
+                .and_then(|v| v.as_str().map(String::from))
+                .filter(|d| !d.is_empty())
+            {
+                model_registry::set_models_base_dir(Some(std::path::PathBuf::from(dir)));
+            }
+
+            // Restore model cache limits
+            if let Some(limits) = app
+                .store("settings.json")
+                .ok()
+                .and_then(|s| s.get("modelCacheLimits"))
+                .and_then(|v| serde_json::from_value(v).ok())
+            {
+                transcriber::set_cache_limits(limits);
+            }
+
             // Create overlay window (hidden by default)
             windows::create_overlay_window(&app.handle())?;
 
@@ -142,6 +237,16 @@ pub fn run() {
                 dock_menu::setup_dock_menu(&app.handle());
             }
 
+            // Refresh the model catalog from the admin-configured source, if any
+            {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = commands::refresh_model_catalog_from_settings(&handle).await {
+                        eprintln!("[audioshift] Model catalog refresh failed: {}", e);
+                    }
+                });
+            }
+
             // Preload AI model in background for faster first transcription
             {
                 let live_model = app
@@ -150,6 +255,7 @@ pub fn run() {
                     .and_then(|s| s.get("liveModel"))
                     .and_then(|v| v.as_str().map(String::from))
                     .unwrap_or_else(|| model_registry::DEFAULT_MODEL_ID.to_string());
+                let live_model = transcriber::preload_target(&live_model);
                 let handle = app.handle().clone();
                 tauri::async_runtime::spawn(async move {
                     if model_registry::model_ready(&live_model) {
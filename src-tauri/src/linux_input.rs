@@ -0,0 +1,205 @@
+//! Paste keystroke injection on Linux: XTest on X11 sessions, and a uinput
+//! virtual keyboard on Wayland, where compositors don't accept synthetic
+//! input from clients. uinput needs write access to /dev/uinput (usually
+//! membership of the `input` group or a udev rule) and sends key positions,
+//! so the chord assumes V is where it is on a QWERTY layout.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use std::time::Duration;
+
+use crate::paster::PasteChord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Session {
+    X11,
+    Wayland,
+}
+
+/// The display server this process talks to, from the environment.
+pub fn detect_session() -> Option<Session> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    match var("XDG_SESSION_TYPE").as_deref() {
+        Some("wayland") => return Some(Session::Wayland),
+        Some("x11") => return Some(Session::X11),
+        _ => {}
+    }
+    if var("WAYLAND_DISPLAY").is_some() {
+        Some(Session::Wayland)
+    } else if var("DISPLAY").is_some() {
+        Some(Session::X11)
+    } else {
+        None
+    }
+}
+
+/// Send the paste chord to the focused window.
+pub fn send_paste(chord: PasteChord) -> Result<()> {
+    match detect_session() {
+        Some(Session::X11) => xtest::send_paste(chord),
+        Some(Session::Wayland) => uinput::send_paste(chord),
+        None => anyhow::bail!("No X11 or Wayland session found (DISPLAY and WAYLAND_DISPLAY are unset)"),
+    }
+}
+
+mod xtest {
+    use super::*;
+    use x11rb::connection::{Connection, RequestConnection};
+    use x11rb::protocol::xproto::{ConnectionExt as _, KEY_PRESS_EVENT, KEY_RELEASE_EVENT};
+    use x11rb::protocol::xtest::{self, ConnectionExt as _};
+    use x11rb::wrapper::ConnectionExt as _;
+
+    const XK_CONTROL_L: u32 = 0xffe3;
+    const XK_SHIFT_L: u32 = 0xffe1;
+    const XK_V: u32 = 0x0076;
+
+    pub fn send_paste(chord: PasteChord) -> Result<()> {
+        let (conn, screen) = x11rb::connect(None).context("Failed to connect to the X server")?;
+        anyhow::ensure!(
+            conn.extension_information(xtest::X11_EXTENSION_NAME)?.is_some(),
+            "The X server doesn't support the XTEST extension"
+        );
+        let root = conn.setup().roots[screen].root;
+
+        // Keysyms rather than fixed keycodes so any layout works
+        let setup = conn.setup();
+        let (min, max) = (setup.min_keycode, setup.max_keycode);
+        let mapping = conn.get_keyboard_mapping(min, max - min + 1)?.reply()?;
+        let per_keycode = mapping.keysyms_per_keycode.max(1) as usize;
+        let keycode = |keysym: u32| -> Result<u8> {
+            mapping
+                .keysyms
+                .chunks(per_keycode)
+                .position(|syms| syms.contains(&keysym))
+                .map(|i| min + i as u8)
+                .with_context(|| format!("No key produces keysym {:#x}", keysym))
+        };
+
+        let mut keys = vec![keycode(XK_CONTROL_L)?];
+        if chord == PasteChord::CtrlShiftV {
+            keys.push(keycode(XK_SHIFT_L)?);
+        }
+        keys.push(keycode(XK_V)?);
+
+        for key in &keys {
+            conn.xtest_fake_input(KEY_PRESS_EVENT, *key, x11rb::CURRENT_TIME, root, 0, 0, 0)?;
+        }
+        for key in keys.iter().rev() {
+            conn.xtest_fake_input(KEY_RELEASE_EVENT, *key, x11rb::CURRENT_TIME, root, 0, 0, 0)?;
+        }
+        conn.sync().context("Failed to send XTest events")?;
+        Ok(())
+    }
+}
+
+mod uinput {
+    use super::*;
+    use std::fs::{File, OpenOptions};
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    const UI_SET_EVBIT: libc::c_ulong = 0x4004_5564;
+    const UI_SET_KEYBIT: libc::c_ulong = 0x4004_5565;
+    const UI_DEV_CREATE: libc::c_ulong = 0x5501;
+
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const SYN_REPORT: u16 = 0;
+    const KEY_LEFTCTRL: u16 = 29;
+    const KEY_LEFTSHIFT: u16 = 42;
+    const KEY_V: u16 = 47;
+    const BUS_VIRTUAL: u16 = 0x06;
+
+    /// Compositors take a moment to pick up a new input device; the device
+    /// is kept open so only the first paste pays for it.
+    const DEVICE_SETTLE: Duration = Duration::from_millis(200);
+
+    static DEVICE: Mutex<Option<File>> = Mutex::new(None);
+
+    fn create_device() -> Result<File> {
+        let file = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Can't open /dev/uinput to send the paste keystroke on Wayland ({}). \
+                     Load the uinput module and give your user write access to it \
+                     (the input group or a udev rule).",
+                    e
+                )
+            })?;
+        let fd = file.as_raw_fd();
+        let ioctl = |request: libc::c_ulong, value: libc::c_int| -> Result<()> {
+            let rc = unsafe { libc::ioctl(fd, request as _, value) };
+            anyhow::ensure!(rc >= 0, "uinput ioctl failed: {}", std::io::Error::last_os_error());
+            Ok(())
+        };
+        ioctl(UI_SET_EVBIT, EV_KEY as libc::c_int)?;
+        for key in [KEY_LEFTCTRL, KEY_LEFTSHIFT, KEY_V] {
+            ioctl(UI_SET_KEYBIT, key as libc::c_int)?;
+        }
+
+        let mut dev: libc::uinput_user_dev = unsafe { std::mem::zeroed() };
+        for (dst, src) in dev.name.iter_mut().zip(b"AudioShift virtual keyboard") {
+            *dst = *src as libc::c_char;
+        }
+        dev.id.bustype = BUS_VIRTUAL;
+        dev.id.vendor = 0x1;
+        dev.id.product = 0x1;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                &dev as *const libc::uinput_user_dev as *const u8,
+                std::mem::size_of::<libc::uinput_user_dev>(),
+            )
+        };
+        (&file).write_all(bytes).context("Failed to set up uinput device")?;
+        let rc = unsafe { libc::ioctl(fd, UI_DEV_CREATE as _) };
+        anyhow::ensure!(rc >= 0, "Failed to create uinput device: {}", std::io::Error::last_os_error());
+
+        std::thread::sleep(DEVICE_SETTLE);
+        Ok(file)
+    }
+
+    fn write_event(file: &File, kind: u16, code: u16, value: i32) -> Result<()> {
+        let mut event: libc::input_event = unsafe { std::mem::zeroed() };
+        event.type_ = kind;
+        event.code = code;
+        event.value = value;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                &event as *const libc::input_event as *const u8,
+                std::mem::size_of::<libc::input_event>(),
+            )
+        };
+        let mut file = file;
+        file.write_all(bytes).context("Failed to write uinput event")
+    }
+
+    fn press(file: &File, keys: &[u16], value: i32) -> Result<()> {
+        for key in keys {
+            write_event(file, EV_KEY, *key, value)?;
+            write_event(file, EV_SYN, SYN_REPORT, 0)?;
+        }
+        Ok(())
+    }
+
+    pub fn send_paste(chord: PasteChord) -> Result<()> {
+        let mut device = DEVICE.lock();
+        let file = match device.take() {
+            Some(file) => file,
+            None => create_device()?,
+        };
+
+        let keys: &[u16] = match chord {
+            PasteChord::CtrlV => &[KEY_LEFTCTRL, KEY_V],
+            PasteChord::CtrlShiftV => &[KEY_LEFTCTRL, KEY_LEFTSHIFT, KEY_V],
+        };
+        let released: Vec<u16> = keys.iter().rev().copied().collect();
+        press(&file, keys, 1).and_then(|_| press(&file, &released, 0))?;
+        // Kept only when it worked, so a broken device is recreated next time
+        *device = Some(file);
+        Ok(())
+    }
+}
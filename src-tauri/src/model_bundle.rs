@@ -0,0 +1,260 @@
+//! Offline model bundles: a tar archive holding a model's files plus a
+//! manifest with checksums, for installing models on machines without
+//! internet access.
+//!
+//! Layout:
+//! ```text
+//! audioshift-model.json     manifest (always the first entry)
+//! <model_id>/<file>         one entry per model file
+//! ```
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+
+use crate::model_registry::{self, CatalogCheck, FileChecksum, ModelDef};
+
+const MANIFEST_NAME: &str = "audioshift-model.json";
+const BUNDLE_FORMAT: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    format: u32,
+    app_version: String,
+    model: ModelDef,
+    /// Checksums keyed by destination file name.
+    files: HashMap<String, FileChecksum>,
+}
+
+/// Write an installed model and its manifest to `dest` as a tar archive.
+pub fn export_model(model_id: &str, dest: &Path) -> Result<()> {
+    let def = model_registry::find_model(model_id)
+        .with_context(|| format!("Unknown model: {}", model_id))?;
+    anyhow::ensure!(
+        model_registry::model_ready(model_id),
+        "Model {} is not installed",
+        model_id
+    );
+
+    let dir = model_registry::model_dir(model_id);
+    let mut files = HashMap::new();
+    for file in &def.files {
+        let name = model_registry::file_dest_name(file);
+        let checksum = model_registry::hash_file(&dir.join(name))?;
+        anyhow::ensure!(
+            model_registry::check_catalog(file, &checksum) != CatalogCheck::Mismatch,
+            "{} failed verification; re-download the model before exporting",
+            name
+        );
+        files.insert(name.to_string(), checksum);
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        model: def.clone(),
+        files,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+
+    let out = File::create(dest).with_context(|| format!("Failed to create {:?}", dest))?;
+    let mut builder = tar::Builder::new(BufWriter::new(out));
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+    for file in &def.files {
+        let name = model_registry::file_dest_name(file);
+        let mut f = File::open(dir.join(name))?;
+        builder.append_file(format!("{}/{}", model_id, name), &mut f)?;
+    }
+    builder.into_inner()?.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    Ok(())
+}
+
+/// A validated bundle whose files sit in a staging directory next to the
+/// models, ready to be moved into place. Dropping it discards the files.
+pub struct StagedBundle {
+    model_id: String,
+    staging: PathBuf,
+}
+
+impl StagedBundle {
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Replace the installed copy of the model with the staged files.
+    /// The caller must make sure the model isn't loaded or downloading.
+    pub fn install(self) -> Result<String> {
+        let dir = model_registry::model_dir(&self.model_id);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::rename(&self.staging, &dir)?;
+        Ok(self.model_id.clone())
+    }
+}
+
+impl Drop for StagedBundle {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.staging);
+    }
+}
+
+/// Validate a bundle and unpack it into a staging directory. Nothing in the
+/// models directory changes until [`StagedBundle::install`], and only once
+/// every checksum matches.
+pub fn stage_bundle(archive: &Path) -> Result<StagedBundle> {
+    let f = File::open(archive).with_context(|| format!("Failed to open {:?}", archive))?;
+    let mut tar = tar::Archive::new(BufReader::new(f));
+    let mut entries = tar.entries()?;
+
+    let manifest: BundleManifest = {
+        let mut first = entries
+            .next()
+            .context("Model bundle is empty")??;
+        anyhow::ensure!(
+            first.path()?.to_str() == Some(MANIFEST_NAME),
+            "Not an AudioShift model bundle"
+        );
+        let mut json = String::new();
+        first.read_to_string(&mut json)?;
+        serde_json::from_str(&json).context("Invalid model bundle manifest")?
+    };
+    anyhow::ensure!(
+        manifest.format == BUNDLE_FORMAT,
+        "Unsupported model bundle format {}",
+        manifest.format
+    );
+
+    let model_id = manifest.model.id.clone();
+    let def = model_registry::find_model(&model_id).with_context(|| {
+        format!("Model {} is not in this app's model catalog", model_id)
+    })?;
+    for file in &def.files {
+        let name = model_registry::file_dest_name(file);
+        let checksum = manifest
+            .files
+            .get(name)
+            .with_context(|| format!("Model bundle is missing {}", name))?;
+        match model_registry::check_catalog(file, checksum) {
+            CatalogCheck::Mismatch => {
+                anyhow::bail!("{} in the bundle doesn't match the model catalog", name)
+            }
+            CatalogCheck::Unverified => eprintln!(
+                "[audioshift] {} in the bundle is unverified: the catalog publishes no checksum for it",
+                name
+            ),
+            CatalogCheck::Verified => {}
+        }
+    }
+    anyhow::ensure!(
+        manifest.files.len() == def.files.len(),
+        "Model bundle contains files that aren't part of {}",
+        model_id
+    );
+
+    let base = model_registry::models_base_dir();
+    let staged = StagedBundle {
+        staging: base.join(format!(".import-{}", model_id)),
+        model_id,
+    };
+    if staged.staging.exists() {
+        std::fs::remove_dir_all(&staged.staging)?;
+    }
+    std::fs::create_dir_all(&staged.staging)?;
+
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let name = path
+            .strip_prefix(&staged.model_id)
+            .ok()
+            .and_then(|p| p.to_str())
+            .filter(|n| manifest.files.contains_key(*n))
+            .with_context(|| format!("Unexpected entry in model bundle: {:?}", path))?
+            .to_string();
+        let dest = staged.staging.join(&name);
+        let mut out = File::create(&dest)?;
+        std::io::copy(&mut entry, &mut out)?;
+    }
+
+    for (name, expected) in &manifest.files {
+        let actual = model_registry::hash_file(&staged.staging.join(name))
+            .with_context(|| format!("Model bundle is missing {}", name))?;
+        anyhow::ensure!(
+            actual.size == expected.size && actual.sha256.eq_ignore_ascii_case(&expected.sha256),
+            "Checksum mismatch for {} in model bundle",
+            name
+        );
+    }
+    model_registry::save_checksums(&staged.staging, &manifest.files)?;
+    Ok(staged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_registry::tests::{install_test_model, reset, MODEL};
+    use crate::model_registry::{ModelState, TEST_LOCK};
+
+    const FIRST: &[u8] = b"first model file";
+    const SECOND: &[u8] = b"second model file";
+
+    /// Install the test model and export it to a bundle in `base`.
+    fn export(name: &str) -> (PathBuf, PathBuf) {
+        let base = install_test_model(name, &[("a.bin", FIRST), ("b.bin", SECOND)]);
+        let dir = model_registry::model_dir(MODEL);
+        std::fs::write(dir.join("a.bin"), FIRST).unwrap();
+        std::fs::write(dir.join("b.bin"), SECOND).unwrap();
+        let bundle = base.join("model.tar");
+        export_model(MODEL, &bundle).unwrap();
+        (base, bundle)
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        let _guard = TEST_LOCK.lock();
+        let (base, bundle) = export("bundle-round-trip");
+        std::fs::remove_dir_all(model_registry::model_dir(MODEL)).unwrap();
+        assert_eq!(model_registry::model_state(MODEL), ModelState::Missing);
+
+        let staged = stage_bundle(&bundle).unwrap();
+        assert_eq!(staged.model_id(), MODEL);
+        assert_eq!(staged.install().unwrap(), MODEL);
+
+        let dir = model_registry::model_dir(MODEL);
+        assert_eq!(std::fs::read(dir.join("a.bin")).unwrap(), FIRST);
+        assert_eq!(std::fs::read(dir.join("b.bin")).unwrap(), SECOND);
+        assert_eq!(model_registry::model_state(MODEL), ModelState::Ready);
+        assert!(model_registry::verify_model(MODEL).unwrap().corrupt.is_empty());
+        assert!(!base.join(format!(".import-{}", MODEL)).exists());
+        reset(&base);
+    }
+
+    #[test]
+    fn tampered_bundle_is_rejected() {
+        let _guard = TEST_LOCK.lock();
+        let (base, bundle) = export("bundle-tampered");
+        let mut data = std::fs::read(&bundle).unwrap();
+        let at = data.windows(SECOND.len()).position(|w| w == SECOND).unwrap();
+        data[at] ^= 0x20;
+        std::fs::write(&bundle, data).unwrap();
+
+        let err = stage_bundle(&bundle).err().unwrap();
+        assert!(err.to_string().contains("Checksum mismatch for b.bin"), "{}", err);
+        // The installed copy is untouched and the staging files are gone.
+        let dir = model_registry::model_dir(MODEL);
+        assert_eq!(std::fs::read(dir.join("b.bin")).unwrap(), SECOND);
+        assert!(!base.join(format!(".import-{}", MODEL)).exists());
+        reset(&base);
+    }
+}
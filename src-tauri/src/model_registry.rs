@@ -1,94 +1,335 @@
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Engine {
     Parakeet,
     Whisper,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ModelDef {
-    pub id: &'static str,
-    pub name: &'static str,
+    pub id: String,
+    pub name: String,
     pub engine: Engine,
-    pub description: &'static str,
+    pub description: String,
     pub approx_bytes: u64,
-    pub files: &'static [ModelFile],
+    /// ISO 639-1 codes the model handles; `"*"` means every language the engine supports.
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub capabilities: Capabilities,
+    #[serde(default)]
+    pub performance: Option<Performance>,
+    pub files: Vec<ModelFile>,
 }
 
+/// Resource and speed figures used to recommend models for a machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Performance {
+    /// Peak memory while transcribing.
+    pub ram_bytes: u64,
+    /// Processing time divided by audio duration on the reference machine
+    /// (8 CPU cores with AVX2 or NEON, no GPU). Below 1.0 is faster than real time.
+    pub realtime_factor: f64,
+    /// Relative accuracy within the catalog; higher is better.
+    pub accuracy: u8,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Capabilities {
+    /// Can translate speech to English.
+    pub translate: bool,
+    /// Can detect the spoken language on its own.
+    pub language_detection: bool,
+    /// Can run on the GPU where available.
+    pub gpu: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ModelFile {
-    pub url: &'static str,
+    pub url: String,
     /// Rename the downloaded file to this name (if Some).
-    pub rename_to: Option<&'static str>,
+    #[serde(default)]
+    pub rename_to: Option<String>,
+    /// Expected SHA-256 of the file as lowercase hex (if published).
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Expected size of the file in bytes (if published).
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+/// Versioned model manifest. `schema_version` guards the format,
+/// `version` orders catalog revisions so an older one never replaces a newer one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Catalog {
+    pub schema_version: u32,
+    pub version: u64,
+    pub models: Vec<ModelDef>,
+}
+
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+
+/// Catalog shipped with the app, used until a newer one is installed.
+const BUNDLED_CATALOG: &str = include_str!("../catalog/models.json");
+
+/// Last catalog fetched from a configured source, reused across restarts.
+const CACHED_CATALOG_FILE: &str = "model-catalog.json";
+
+static CATALOG: RwLock<Option<Arc<Catalog>>> = RwLock::new(None);
+
+/// On-disk state of a model, from cheapest to most complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelState {
+    /// No model files are present.
+    Missing,
+    /// Some files are present, others still need downloading.
+    Partial,
+    /// At least one file failed a size or checksum check.
+    Corrupt,
+    Ready,
+}
+
+/// Checksums recorded when a file finishes downloading. They only catch a file
+/// changing on disk afterwards; a file counts as verified only when it
+/// matches a hash the catalog publishes.
+const CHECKSUMS_FILE: &str = "checksums.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChecksum {
+    pub sha256: String,
+    pub size: u64,
 }
 
 pub const DEFAULT_MODEL_ID: &str = "parakeet-tdt-0.6b-v3";
 
-pub static MODELS: &[ModelDef] = &[
-    ModelDef {
-        id: "parakeet-tdt-0.6b-v3",
-        name: "Parakeet TDT 0.6b v3",
-        engine: Engine::Parakeet,
-        description: "Fast, accurate English transcription. Best balance of speed and quality.",
-        approx_bytes: 680_000_000,
-        files: &[
-            ModelFile { url: "https://huggingface.co/istupakov/parakeet-tdt-0.6b-v3-onnx/resolve/main/encoder-model.int8.onnx", rename_to: Some("encoder-model.onnx") },
-            ModelFile { url: "https://huggingface.co/istupakov/parakeet-tdt-0.6b-v3-onnx/resolve/main/decoder_joint-model.int8.onnx", rename_to: Some("decoder_joint-model.onnx") },
-            ModelFile { url: "https://huggingface.co/istupakov/parakeet-tdt-0.6b-v3-onnx/resolve/main/vocab.txt", rename_to: None },
-            ModelFile { url: "https://huggingface.co/istupakov/parakeet-tdt-0.6b-v3-onnx/resolve/main/config.json", rename_to: None },
-            ModelFile { url: "https://huggingface.co/istupakov/parakeet-tdt-0.6b-v3-onnx/resolve/main/nemo128.onnx", rename_to: None },
-        ],
-    },
-    ModelDef {
-        id: "whisper-large-v3-turbo-q5_0",
-        name: "Whisper Large v3 Turbo (Q5)",
-        engine: Engine::Whisper,
-        description: "Multilingual, highly accurate. Supports 100+ languages.",
-        approx_bytes: 574_000_000,
-        files: &[
-            ModelFile { url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin", rename_to: Some("model.bin") },
-        ],
-    },
-    ModelDef {
-        id: "whisper-large-v3-turbo-q8_0",
-        name: "Whisper Large v3 Turbo (Q8)",
-        engine: Engine::Whisper,
-        description: "Multilingual, highest accuracy. Higher quality quantization.",
-        approx_bytes: 874_000_000,
-        files: &[
-            ModelFile { url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q8_0.bin", rename_to: Some("model.bin") },
-        ],
-    },
-    ModelDef {
-        id: "whisper-medium-q5_0",
-        name: "Whisper Medium (Q5)",
-        engine: Engine::Whisper,
-        description: "Multilingual, moderate speed and accuracy. Good middle ground.",
-        approx_bytes: 539_000_000,
-        files: &[
-            ModelFile { url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium-q5_0.bin", rename_to: Some("model.bin") },
-        ],
-    },
-    ModelDef {
-        id: "whisper-small-q5_1",
-        name: "Whisper Small (Q5)",
-        engine: Engine::Whisper,
-        description: "Multilingual, fastest Whisper model. Smallest download.",
-        approx_bytes: 190_000_000,
-        files: &[
-            ModelFile { url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small-q5_1.bin", rename_to: Some("model.bin") },
-        ],
-    },
-];
-
-pub fn find_model(id: &str) -> Option<&'static ModelDef> {
-    MODELS.iter().find(|m| m.id == id)
-}
-
-fn models_base_dir() -> PathBuf {
+/// Pseudo model id for the live model: detect the spoken language, then use
+/// Parakeet for languages it covers and a Whisper model for the rest.
+pub const AUTO_MODEL_ID: &str = "auto";
+
+/// Whisper model used by [`AUTO_MODEL_ID`] unless another one is configured.
+pub const DEFAULT_AUTO_WHISPER_MODEL_ID: &str = "whisper-large-v3-turbo-q5_0";
+
+fn app_data_dir() -> PathBuf {
     let data_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
     let app_id = "io.audioshift.desktop";
-    data_dir.join(app_id).join("models")
+    data_dir.join(app_id)
+}
+
+/// User-chosen models directory (`modelsDir` setting); `None` means the default.
+static MODELS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+pub fn default_models_base_dir() -> PathBuf {
+    app_data_dir().join("models")
+}
+
+pub fn models_base_dir() -> PathBuf {
+    MODELS_DIR
+        .read()
+        .clone()
+        .unwrap_or_else(default_models_base_dir)
+}
+
+pub fn set_models_base_dir(dir: Option<PathBuf>) {
+    *MODELS_DIR.write() = dir;
+}
+
+/// Move every model directory from `from` into `to`. Uses a rename where
+/// possible and falls back to copy + delete across filesystems. Models that
+/// already exist in `to` are left in place in `from`.
+/// Returns the ids of the models that were moved.
+pub fn migrate_models(from: &Path, to: &Path) -> Result<Vec<String>> {
+    if from.exists() {
+        let (from_abs, to_abs) = (canonical(from)?, canonical(to)?);
+        anyhow::ensure!(from_abs != to_abs, "The models are already in {:?}", to);
+        anyhow::ensure!(
+            !to_abs.starts_with(&from_abs) && !from_abs.starts_with(&to_abs),
+            "Can't move models between {:?} and {:?}: one folder is inside the other",
+            from,
+            to
+        );
+    }
+    std::fs::create_dir_all(to).with_context(|| format!("Failed to create {:?}", to))?;
+    if !from.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut moved = Vec::new();
+    for entry in std::fs::read_dir(from)?.flatten() {
+        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            continue;
+        }
+        let name = entry.file_name();
+        let dest = to.join(&name);
+        if dest.exists() {
+            eprintln!("[audioshift] Skipping model migration of {:?}: already exists in {:?}", name, to);
+            continue;
+        }
+        if std::fs::rename(entry.path(), &dest).is_err() {
+            copy_dir(&entry.path(), &dest)
+                .with_context(|| format!("Failed to copy {:?} to {:?}", name, to))?;
+            std::fs::remove_dir_all(entry.path())?;
+        }
+        moved.push(name.to_string_lossy().to_string());
+    }
+    Ok(moved)
+}
+
+/// Absolute form of `path` with symlinks resolved. Components past the
+/// deepest existing ancestor are appended as given, so it works for folders
+/// that haven't been created yet.
+fn canonical(path: &Path) -> Result<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(base) => return Ok(rest.iter().rev().fold(base, |p, c| p.join(c))),
+            Err(_) => {
+                rest.push(existing.file_name().with_context(|| format!("Invalid folder {:?}", path))?);
+                existing = existing.parent().with_context(|| format!("Invalid folder {:?}", path))?;
+            }
+        }
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)?.flatten() {
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse and sanity-check a catalog manifest.
+pub fn parse_catalog(json: &str) -> Result<Catalog> {
+    let catalog: Catalog = serde_json::from_str(json).context("Invalid model catalog")?;
+    anyhow::ensure!(
+        catalog.schema_version == CATALOG_SCHEMA_VERSION,
+        "Unsupported model catalog schema version {} (expected {})",
+        catalog.schema_version,
+        CATALOG_SCHEMA_VERSION
+    );
+
+    let mut ids = HashSet::new();
+    for model in &catalog.models {
+        anyhow::ensure!(
+            !model.id.is_empty() && !model.id.contains(['/', '\\']) && model.id != "." && model.id != "..",
+            "Invalid model id: {:?}",
+            model.id
+        );
+        anyhow::ensure!(ids.insert(model.id.as_str()), "Duplicate model id: {}", model.id);
+        anyhow::ensure!(!model.files.is_empty(), "Model {} has no files", model.id);
+        for file in &model.files {
+            let name = file_dest_name(file);
+            anyhow::ensure!(
+                !name.is_empty() && !name.contains(['/', '\\']) && name != "." && name != "..",
+                "Model {} has an invalid file name: {:?}",
+                model.id,
+                name
+            );
+        }
+    }
+    Ok(catalog)
+}
+
+fn bundled_catalog() -> Catalog {
+    parse_catalog(BUNDLED_CATALOG).expect("bundled model catalog is valid")
+}
+
+/// The active catalog: the newest of the bundled and the cached manifest.
+pub fn catalog() -> Arc<Catalog> {
+    if let Some(c) = CATALOG.read().as_ref() {
+        return c.clone();
+    }
+    let mut lock = CATALOG.write();
+    if let Some(c) = lock.as_ref() {
+        return c.clone();
+    }
+
+    let bundled = bundled_catalog();
+    let cached = std::fs::read_to_string(app_data_dir().join(CACHED_CATALOG_FILE))
+        .ok()
+        .and_then(|data| match parse_catalog(&data) {
+            Ok(c) => Some(c),
+            Err(e) => {
+                eprintln!("[audioshift] Ignoring cached model catalog: {}", e);
+                None
+            }
+        });
+    let active = match cached {
+        Some(c) if c.version >= bundled.version => c,
+        _ => bundled,
+    };
+    let active = Arc::new(active);
+    *lock = Some(active.clone());
+    active
+}
+
+/// Replace the active catalog and persist it for the next launch.
+/// Returns false (and keeps the current catalog) if `new` is older.
+pub fn install_catalog(new: Catalog) -> Result<bool> {
+    if new.version < catalog().version {
+        return Ok(false);
+    }
+    let dir = app_data_dir();
+    std::fs::create_dir_all(&dir)?;
+    let json = serde_json::to_string_pretty(&new)?;
+    std::fs::write(dir.join(CACHED_CATALOG_FILE), json)
+        .context("Failed to write model catalog cache")?;
+    *CATALOG.write() = Some(Arc::new(new));
+    Ok(true)
+}
+
+/// Fetch a catalog from an `http(s)://` URL or a local file path
+/// (optionally prefixed with `file://`).
+pub async fn fetch_catalog(client: &reqwest::Client, source: &str) -> Result<Catalog> {
+    let json = if source.starts_with("http://") || source.starts_with("https://") {
+        client
+            .get(source)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+            .context("Failed to read model catalog response")?
+    } else {
+        let path = source.strip_prefix("file://").unwrap_or(source);
+        tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read model catalog from {}", path))?
+    };
+    parse_catalog(&json)
+}
+
+pub fn models() -> Vec<ModelDef> {
+    catalog().models.clone()
+}
+
+pub fn find_model(id: &str) -> Option<ModelDef> {
+    catalog().models.iter().find(|m| m.id == id).cloned()
+}
+
+/// Whether `model` can transcribe speech in `language` (ISO 639-1).
+pub fn supports_language(model: &ModelDef, language: &str) -> bool {
+    model.languages.iter().any(|l| l == "*" || l == language)
 }
 
 pub fn model_dir(id: &str) -> PathBuf {
@@ -96,18 +337,225 @@ pub fn model_dir(id: &str) -> PathBuf {
 }
 
 pub fn model_ready(id: &str) -> bool {
+    model_state(id) == ModelState::Ready
+}
+
+/// Cheap integrity check: existence, expected size and corruption markers.
+/// Full checksum verification is done by [`verify_model`].
+pub fn model_state(id: &str) -> ModelState {
+    let def = match find_model(id) {
+        Some(d) => d,
+        None => return ModelState::Missing,
+    };
+    let dir = model_dir(id);
+    let recorded = load_checksums(&dir);
+    let mut present = 0;
+    for file in &def.files {
+        let path = dir.join(file_dest_name(file));
+        if !path.exists() {
+            continue;
+        }
+        if !file_intact(&path, file, &recorded) {
+            return ModelState::Corrupt;
+        }
+        present += 1;
+    }
+    match present {
+        0 => ModelState::Missing,
+        n if n == def.files.len() => ModelState::Ready,
+        _ => ModelState::Partial,
+    }
+}
+
+/// Files of a model that are missing or known to be corrupt.
+pub fn files_needing_download(id: &str) -> Vec<ModelFile> {
     let def = match find_model(id) {
         Some(d) => d,
-        None => return false,
+        None => return Vec::new(),
     };
     let dir = model_dir(id);
-    for file in def.files {
+    let recorded = load_checksums(&dir);
+    def.files
+        .into_iter()
+        .filter(|file| {
+            let path = dir.join(file_dest_name(file));
+            !path.exists() || !file_intact(&path, file, &recorded)
+        })
+        .collect()
+}
+
+fn file_intact(path: &Path, file: &ModelFile, recorded: &HashMap<String, FileChecksum>) -> bool {
+    if corrupt_marker(path).exists() {
+        return false;
+    }
+    let expected_size = file
+        .size
+        .or_else(|| recorded.get(file_dest_name(file)).map(|c| c.size));
+    match expected_size {
+        Some(size) => std::fs::metadata(path).map(|m| m.len() == size).unwrap_or(false),
+        None => true,
+    }
+}
+
+/// Marker written next to a file that failed checksum verification.
+pub fn corrupt_marker(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".corrupt");
+    path.with_file_name(name)
+}
+
+fn load_checksums(dir: &Path) -> HashMap<String, FileChecksum> {
+    std::fs::read_to_string(dir.join(CHECKSUMS_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_checksums(dir: &Path, checksums: &HashMap<String, FileChecksum>) -> Result<()> {
+    let json = serde_json::to_string_pretty(checksums)?;
+    std::fs::write(dir.join(CHECKSUMS_FILE), json).context("Failed to write checksums.json")
+}
+
+/// Compute the SHA-256 (lowercase hex) and size of a file.
+pub fn hash_file(path: &Path) -> Result<FileChecksum> {
+    use sha2::{Digest, Sha256};
+
+    let mut f = std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    let mut size: u64 = 0;
+    loop {
+        let n = f.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    let sha256 = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(FileChecksum { sha256, size })
+}
+
+/// Outcome of checking a file against its catalog entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogCheck {
+    /// Matches the published SHA-256 (and size, if published).
+    Verified,
+    /// The catalog publishes no SHA-256 for the file, so there's nothing
+    /// trustworthy to check it against. A published size still matched.
+    Unverified,
+    Mismatch,
+}
+
+/// Check a file's checksum against whatever the catalog publishes for it.
+pub fn check_catalog(file: &ModelFile, actual: &FileChecksum) -> CatalogCheck {
+    if file.size.is_some_and(|s| s != actual.size) {
+        return CatalogCheck::Mismatch;
+    }
+    match file.sha256.as_deref() {
+        Some(h) if h.eq_ignore_ascii_case(&actual.sha256) => CatalogCheck::Verified,
+        Some(_) => CatalogCheck::Mismatch,
+        None => CatalogCheck::Unverified,
+    }
+}
+
+/// Error returned when a downloaded file doesn't match the catalog.
+#[derive(Debug)]
+pub struct ChecksumMismatch {
+    pub name: String,
+    pub actual: FileChecksum,
+}
+
+impl std::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Checksum mismatch for {} (got {} bytes, sha256 {})",
+            self.name, self.actual.size, self.actual.sha256
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// Verify a freshly downloaded file against the catalog and record its
+/// checksum so later changes to it can be detected. On mismatch the file is
+/// removed so the next download attempt starts clean.
+pub fn verify_downloaded_file(id: &str, file: &ModelFile) -> Result<()> {
+    let dir = model_dir(id);
+    let name = file_dest_name(file);
+    let path = dir.join(name);
+    let actual = hash_file(&path)?;
+
+    match check_catalog(file, &actual) {
+        CatalogCheck::Mismatch => {
+            let _ = std::fs::remove_file(&path);
+            return Err(ChecksumMismatch { name: name.to_string(), actual }.into());
+        }
+        CatalogCheck::Unverified => {
+            eprintln!(
+                "[audioshift] {} of {} is unverified: the catalog publishes no checksum for it",
+                name, id
+            );
+        }
+        CatalogCheck::Verified => {}
+    }
+
+    let _ = std::fs::remove_file(corrupt_marker(&path));
+    let mut recorded = load_checksums(&dir);
+    recorded.insert(name.to_string(), actual);
+    save_checksums(&dir, &recorded)
+}
+
+/// Result of [`verify_model`], by destination file name.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelVerification {
+    /// Files that don't match the catalog or changed since they were downloaded.
+    pub corrupt: Vec<String>,
+    /// Files the catalog publishes no checksum for.
+    pub unverified: Vec<String>,
+}
+
+/// Fully re-hash every present file of a model. Files that don't match the
+/// catalog, or changed since the checksum recorded at download time, get a
+/// corruption marker so [`model_state`] reports `Corrupt` and only they are
+/// re-downloaded.
+pub fn verify_model(id: &str) -> Result<ModelVerification> {
+    let def = find_model(id).with_context(|| format!("Unknown model: {}", id))?;
+    let dir = model_dir(id);
+    let recorded = load_checksums(&dir);
+    let mut result = ModelVerification::default();
+
+    for file in &def.files {
         let name = file_dest_name(file);
-        if !dir.join(name).exists() {
-            return false;
+        let path = dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        let actual = hash_file(&path)?;
+        let changed = recorded
+            .get(name)
+            .is_some_and(|c| c.size != actual.size || !c.sha256.eq_ignore_ascii_case(&actual.sha256));
+
+        let corrupt = match check_catalog(file, &actual) {
+            CatalogCheck::Verified => false,
+            CatalogCheck::Unverified => {
+                if !changed {
+                    result.unverified.push(name.to_string());
+                }
+                changed
+            }
+            CatalogCheck::Mismatch => true,
+        };
+        if corrupt {
+            std::fs::write(corrupt_marker(&path), b"")?;
+            result.corrupt.push(name.to_string());
+        } else {
+            let _ = std::fs::remove_file(corrupt_marker(&path));
         }
     }
-    true
+    Ok(result)
 }
 
 pub fn model_disk_size(id: &str) -> u64 {
@@ -126,12 +574,12 @@ pub fn model_disk_size(id: &str) -> u64 {
 }
 
 pub fn any_model_ready() -> bool {
-    MODELS.iter().any(|m| model_ready(m.id))
+    catalog().models.iter().any(|m| model_ready(&m.id))
 }
 
 /// Get the destination filename for a model file (after optional rename).
 pub fn file_dest_name(file: &ModelFile) -> &str {
-    if let Some(name) = file.rename_to {
+    if let Some(name) = &file.rename_to {
         name
     } else {
         file.url.rsplit('/').next().unwrap_or("unknown")
@@ -146,3 +594,165 @@ pub fn size_label(bytes: u64) -> String {
         format!("{} MB", bytes / 1_000_000)
     }
 }
+
+/// Serializes tests that swap the global catalog or models directory.
+#[cfg(test)]
+pub(crate) static TEST_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const MODEL: &str = "test-model";
+
+    /// Install a one-model catalog whose files publish the checksums of
+    /// `contents`, with the models directory in a fresh temp dir.
+    pub(crate) fn install_test_model(name: &str, contents: &[(&str, &[u8])]) -> PathBuf {
+        let base = std::env::temp_dir().join(format!("audioshift-models-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let staging = base.join(".staging");
+        std::fs::create_dir_all(&staging).unwrap();
+
+        let files = contents
+            .iter()
+            .map(|(file, data)| {
+                let path = staging.join(file);
+                std::fs::write(&path, data).unwrap();
+                let actual = hash_file(&path).unwrap();
+                ModelFile {
+                    url: format!("https://example.com/{}", file),
+                    rename_to: None,
+                    sha256: Some(actual.sha256),
+                    size: Some(actual.size),
+                }
+            })
+            .collect();
+        let model = ModelDef {
+            id: MODEL.to_string(),
+            name: "Test".to_string(),
+            engine: Engine::Whisper,
+            description: String::new(),
+            approx_bytes: 0,
+            languages: vec!["*".to_string()],
+            capabilities: Capabilities::default(),
+            performance: None,
+            files,
+        };
+        *CATALOG.write() = Some(Arc::new(Catalog {
+            schema_version: CATALOG_SCHEMA_VERSION,
+            version: 0,
+            models: vec![model],
+        }));
+        set_models_base_dir(Some(base.clone()));
+        std::fs::create_dir_all(model_dir(MODEL)).unwrap();
+        base
+    }
+
+    pub(crate) fn reset(base: &Path) {
+        *CATALOG.write() = None;
+        set_models_base_dir(None);
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    fn needing_download() -> Vec<String> {
+        files_needing_download(MODEL).iter().map(|f| file_dest_name(f).to_string()).collect()
+    }
+
+    #[test]
+    fn size_mismatch_is_corrupt() {
+        let _guard = TEST_LOCK.lock();
+        let base = install_test_model("size", &[("a.bin", b"abcd"), ("b.bin", b"wxyz")]);
+        let dir = model_dir(MODEL);
+        std::fs::write(dir.join("a.bin"), b"abcd").unwrap();
+        std::fs::write(dir.join("b.bin"), b"wxyz!").unwrap();
+
+        assert_eq!(model_state(MODEL), ModelState::Corrupt);
+        assert_eq!(needing_download(), vec!["b.bin"]);
+        reset(&base);
+    }
+
+    #[test]
+    fn hash_mismatch_is_corrupt_after_verify() {
+        let _guard = TEST_LOCK.lock();
+        let base = install_test_model("hash", &[("a.bin", b"abcd"), ("b.bin", b"wxyz")]);
+        let dir = model_dir(MODEL);
+        std::fs::write(dir.join("a.bin"), b"abcd").unwrap();
+        std::fs::write(dir.join("b.bin"), b"WXYZ").unwrap();
+
+        // Same size, so the cheap check can't tell until the files are hashed.
+        assert_eq!(model_state(MODEL), ModelState::Ready);
+        let verification = verify_model(MODEL).unwrap();
+        assert_eq!(verification.corrupt, vec!["b.bin"]);
+        assert!(verification.unverified.is_empty());
+
+        assert_eq!(model_state(MODEL), ModelState::Corrupt);
+        assert_eq!(needing_download(), vec!["b.bin"]);
+        reset(&base);
+    }
+
+    #[test]
+    fn intact_model_is_ready() {
+        let _guard = TEST_LOCK.lock();
+        let base = install_test_model("ready", &[("a.bin", b"abcd"), ("b.bin", b"wxyz")]);
+        let dir = model_dir(MODEL);
+        std::fs::write(dir.join("a.bin"), b"abcd").unwrap();
+
+        assert_eq!(model_state(MODEL), ModelState::Partial);
+        std::fs::write(dir.join("b.bin"), b"wxyz").unwrap();
+        assert!(verify_model(MODEL).unwrap().corrupt.is_empty());
+        assert_eq!(model_state(MODEL), ModelState::Ready);
+        assert!(needing_download().is_empty());
+        reset(&base);
+    }
+
+    fn temp_base(name: &str) -> PathBuf {
+        let base = std::env::temp_dir().join(format!("audioshift-migrate-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(&base).unwrap();
+        base
+    }
+
+    #[test]
+    fn migrate_moves_models() {
+        let base = temp_base("move");
+        let (from, to) = (base.join("old"), base.join("new"));
+        std::fs::create_dir_all(from.join("model-a")).unwrap();
+        std::fs::write(from.join("model-a").join("model.bin"), b"abcd").unwrap();
+        std::fs::create_dir_all(to.join("model-b")).unwrap();
+        std::fs::create_dir_all(from.join("model-b")).unwrap();
+
+        assert_eq!(migrate_models(&from, &to).unwrap(), vec!["model-a"]);
+        assert_eq!(std::fs::read(to.join("model-a").join("model.bin")).unwrap(), b"abcd");
+        assert!(!from.join("model-a").exists());
+        // Already present in the destination, so left where it was.
+        assert!(from.join("model-b").exists());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn migrate_rejects_nested_folders() {
+        let base = temp_base("nested");
+        let outer = base.join("models");
+        std::fs::create_dir_all(outer.join("model-a")).unwrap();
+        let inner = outer.join("sub");
+
+        assert!(migrate_models(&outer, &inner).is_err());
+        assert!(!inner.exists());
+        std::fs::create_dir_all(inner.join("model-b")).unwrap();
+        assert!(migrate_models(&inner, &outer).is_err());
+        assert!(outer.join("model-a").exists() && inner.join("model-b").exists());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn migrate_rejects_same_folder() {
+        let base = temp_base("same");
+        let dir = base.join("models");
+        std::fs::create_dir_all(dir.join("model-a")).unwrap();
+
+        assert!(migrate_models(&dir, &dir).is_err());
+        assert!(migrate_models(&dir, &dir.join("..").join("models")).is_err());
+        assert!(dir.join("model-a").exists());
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -1,4 +1,133 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::typer;
+
+const RESTORE_KEY: &str = "clipboardRestore";
+const CHORD_KEY: &str = "pasteChord";
+
+/// Keystroke that pastes on Linux, where terminals use Ctrl+Shift+V.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteChord {
+    #[default]
+    CtrlV,
+    CtrlShiftV,
+}
+
+pub fn stored_paste_chord(app: &AppHandle) -> PasteChord {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(CHORD_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_paste_chord(app: &AppHandle, chord: PasteChord) -> Result<()> {
+    let store = app.store("settings.json")?;
+    store.set(CHORD_KEY, serde_json::to_value(chord)?);
+    Ok(())
+}
+
+/// How transcribed text reaches the focused app (`pasteMode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasteMode {
+    /// Copy and send the paste keystroke.
+    Auto,
+    /// Copy only; the user pastes.
+    Clipboard,
+    /// Send the text as keystrokes, leaving the clipboard alone.
+    Type,
+}
+
+/// Stored global paste mode, defaulting to auto. Typing falls back to auto
+/// where it isn't supported, e.g. for settings copied from another machine.
+pub fn stored_paste_mode(app: &AppHandle) -> PasteMode {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get("pasteMode"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .filter(|mode| *mode != PasteMode::Type || typer::supported())
+        .unwrap_or(PasteMode::Auto)
+}
+
+/// Reject paste modes this platform can't deliver with.
+fn check_paste_mode(mode: PasteMode) -> Result<()> {
+    anyhow::ensure!(
+        mode != PasteMode::Type || typer::supported(),
+        "Typing output is not supported on this platform yet"
+    );
+    Ok(())
+}
+
+pub fn save_paste_mode(app: &AppHandle, mode: PasteMode) -> Result<()> {
+    check_paste_mode(mode)?;
+    let store = app.store("settings.json")?;
+    store.set("pasteMode", serde_json::to_value(mode)?);
+    Ok(())
+}
+
+/// Paste mode for a caller that only knows auto vs clipboard: typing, when
+/// that's the stored mode, takes the place of auto-paste.
+pub fn paste_mode(app: &AppHandle, auto_paste: bool) -> PasteMode {
+    match stored_paste_mode(app) {
+        PasteMode::Type => PasteMode::Type,
+        _ if auto_paste => PasteMode::Auto,
+        _ => PasteMode::Clipboard,
+    }
+}
+
+/// Deliver `text` to the focused app. Blocks while typing. `chord` replaces
+/// the stored paste chord, e.g. for a terminal's profile.
+pub fn output(app: &AppHandle, text: &str, mode: PasteMode, chord: Option<PasteChord>) -> Result<()> {
+    match mode {
+        PasteMode::Auto => {
+            let chord = chord.unwrap_or_else(|| stored_paste_chord(app));
+            paste_text(text, restore_delay(app), chord)
+        }
+        PasteMode::Clipboard => copy_to_clipboard(text),
+        PasteMode::Type => typer::type_text(text, &typer::load_settings(app)),
+    }
+}
+
+/// Put back what was on the clipboard once a paste has landed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClipboardRestore {
+    pub enabled: bool,
+    /// Wait after the paste keystroke; slow apps read the clipboard late.
+    pub delay_ms: u64,
+}
+
+impl Default for ClipboardRestore {
+    fn default() -> Self {
+        Self { enabled: true, delay_ms: 750 }
+    }
+}
+
+pub fn load_restore_settings(app: &AppHandle) -> ClipboardRestore {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(RESTORE_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_restore_settings(app: &AppHandle, settings: &ClipboardRestore) -> Result<()> {
+    let store = app.store("settings.json")?;
+    store.set(RESTORE_KEY, serde_json::to_value(settings)?);
+    Ok(())
+}
+
+/// Delay to pass to [`paste_text`], or `None` when restoring is off.
+pub fn restore_delay(app: &AppHandle) -> Option<Duration> {
+    let settings = load_restore_settings(app);
+    settings.enabled.then(|| Duration::from_millis(settings.delay_ms))
+}
 
 /// Copy text to clipboard only.
 pub fn copy_to_clipboard(text: &str) -> Result<()> {
@@ -22,13 +151,168 @@ pub fn copy_to_clipboard(text: &str) -> Result<()> {
         clipboard
             .set_text(text)
             .map_err(|e| anyhow::anyhow!("Failed to copy to clipboard: {}", e))?;
+        // arboard stops serving the X11 selection once its last instance is
+        // dropped; without a clipboard manager the text would be gone before
+        // the paste keystroke reads it
+        #[cfg(target_os = "linux")]
+        CLIPBOARD_OWNER.lock().replace(clipboard);
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+static CLIPBOARD_OWNER: parking_lot::Mutex<Option<arboard::Clipboard>> = parking_lot::Mutex::new(None);
+
+/// Text currently on the clipboard, or `None` if it holds no text.
+pub fn read_clipboard() -> Result<Option<String>> {
+    #[cfg(target_os = "macos")]
+    {
+        extern "C" {
+            fn read_string_from_pasteboard() -> *mut std::os::raw::c_char;
+            fn free_pasteboard_string(s: *mut std::os::raw::c_char);
+        }
+        let ptr = unsafe { read_string_from_pasteboard() };
+        if ptr.is_null() {
+            return Ok(None);
+        }
+        let text = unsafe { std::ffi::CStr::from_ptr(ptr) }.to_string_lossy().into_owned();
+        unsafe { free_pasteboard_string(ptr) };
+        Ok(Some(text))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        use arboard::Clipboard;
+        let mut clipboard = Clipboard::new()
+            .map_err(|e| anyhow::anyhow!("Failed to access clipboard: {}", e))?;
+        // Images and empty clipboards both report an error here
+        Ok(clipboard.get_text().ok())
+    }
+}
+
+/// Clipboard contents saved before a paste. On macOS every pasteboard item
+/// is kept with all of its types; elsewhere text and images are.
+#[cfg(target_os = "macos")]
+struct Snapshot(*mut std::ffi::c_void);
+
+// The NSArray is only touched on the main thread by the helper
+#[cfg(target_os = "macos")]
+unsafe impl Send for Snapshot {}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn snapshot_pasteboard() -> *mut std::ffi::c_void;
+    fn restore_pasteboard(snapshot: *mut std::ffi::c_void) -> bool;
+    fn free_pasteboard_snapshot(snapshot: *mut std::ffi::c_void);
+    fn pasteboard_change_count() -> std::os::raw::c_long;
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        unsafe { free_pasteboard_snapshot(self.0) };
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+enum Snapshot {
+    Empty,
+    Text(String),
+    Image(arboard::ImageData<'static>),
+}
+
+#[cfg(target_os = "macos")]
+fn snapshot_clipboard() -> Result<Snapshot> {
+    Ok(Snapshot(unsafe { snapshot_pasteboard() }))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn snapshot_clipboard() -> Result<Snapshot> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| anyhow::anyhow!("Failed to access clipboard: {}", e))?;
+    if let Ok(text) = clipboard.get_text() {
+        return Ok(Snapshot::Text(text));
     }
+    Ok(match clipboard.get_image() {
+        Ok(image) => Snapshot::Image(image),
+        Err(_) => Snapshot::Empty,
+    })
+}
 
+#[cfg(target_os = "macos")]
+fn restore_clipboard(mut snapshot: Snapshot) -> Result<()> {
+    // restore_pasteboard releases the items
+    let items = std::mem::replace(&mut snapshot.0, std::ptr::null_mut());
+    anyhow::ensure!(unsafe { restore_pasteboard(items) }, "Failed to restore clipboard");
     Ok(())
 }
 
-/// Copy text to clipboard and simulate paste keystroke.
-pub fn paste_text(text: &str) -> Result<()> {
+#[cfg(not(target_os = "macos"))]
+fn restore_clipboard(snapshot: Snapshot) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| anyhow::anyhow!("Failed to access clipboard: {}", e))?;
+    match snapshot {
+        Snapshot::Empty => clipboard.clear(),
+        Snapshot::Text(text) => clipboard.set_text(text),
+        Snapshot::Image(image) => clipboard.set_image(image),
+    }
+    .map_err(|e| anyhow::anyhow!("Failed to restore clipboard: {}", e))
+}
+
+/// Identifies the clipboard contents just after we copied, to tell whether
+/// the user copied something else before the restore.
+#[cfg(target_os = "macos")]
+fn clipboard_mark(_text: &str) -> std::os::raw::c_long {
+    unsafe { pasteboard_change_count() }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clipboard_mark(text: &str) -> String {
+    text.to_string()
+}
+
+#[cfg(target_os = "macos")]
+fn clipboard_unchanged(mark: &std::os::raw::c_long) -> bool {
+    unsafe { pasteboard_change_count() == *mark }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clipboard_unchanged(mark: &str) -> bool {
+    read_clipboard().ok().flatten().as_deref() == Some(mark)
+}
+
+/// Put `snapshot` back after `delay` on a background thread, unless the
+/// clipboard changed again in the meantime.
+fn restore_later(snapshot: Snapshot, text: &str, delay: Duration) {
+    let mark = clipboard_mark(text);
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        if !clipboard_unchanged(&mark) {
+            eprintln!("[audioshift] Clipboard changed after paste, not restoring it");
+            return;
+        }
+        if let Err(e) = restore_clipboard(snapshot) {
+            eprintln!("[audioshift] {}", e);
+        }
+    });
+}
+
+/// Whether [`paste_text`] sends a paste keystroke here. Without one the text
+/// stays on the clipboard for the user to paste, so it isn't restored.
+const SENDS_PASTE_KEYSTROKE: bool = cfg!(any(target_os = "macos", target_os = "windows", target_os = "linux"));
+
+/// Copy text to clipboard and simulate paste keystroke. With `restore_after`
+/// the previous clipboard contents are put back that long after the paste.
+/// `chord` is only used on Linux; macOS and Windows always use Cmd/Ctrl+V.
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+pub fn paste_text(text: &str, restore_after: Option<Duration>, chord: PasteChord) -> Result<()> {
+    let snapshot = match restore_after.filter(|_| SENDS_PASTE_KEYSTROKE) {
+        Some(_) => snapshot_clipboard()
+            .map_err(|e| eprintln!("[audioshift] Not restoring clipboard: {}", e))
+            .ok(),
+        None => None,
+    };
     copy_to_clipboard(text)?;
 
     #[cfg(target_os = "macos")]
@@ -113,5 +397,19 @@ pub fn paste_text(text: &str) -> Result<()> {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    {
+        // Small delay to let the clipboard owner settle
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        crate::linux_input::send_paste(chord).map_err(|e| {
+            anyhow::anyhow!("Text is on the clipboard, but the paste keystroke couldn't be sent: {:#}", e)
+        })?;
+    }
+
+    if let (Some(snapshot), Some(delay)) = (snapshot, restore_after) {
+        restore_later(snapshot, text, delay);
+    }
+
     Ok(())
 }
@@ -0,0 +1,420 @@
+//! Optional rewrite of the finished transcript by a local LLM (llama.cpp,
+//! Ollama, LM Studio...) through an OpenAI-compatible chat completions
+//! endpoint. Only loopback endpoints are accepted so transcripts never leave
+//! the machine. Any failure or timeout falls back to the unpolished text.
+//! The optional API key is kept in the system keychain, not in settings.json.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+use crate::postprocess::snippets;
+
+const SETTINGS_KEY: &str = "polish";
+
+/// Keychain item holding [`PolishSettings::api_key`].
+const KEYCHAIN_SERVICE: &str = "io.audioshift.desktop";
+const KEYCHAIN_ACCOUNT: &str = "polish-api-key";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Preset {
+    FixGrammar,
+    Concise,
+    BulletList,
+    FormalEmail,
+    /// Uses [`PolishSettings::custom_instruction`].
+    Custom,
+}
+
+impl Preset {
+    fn instruction(self) -> &'static str {
+        match self {
+            Preset::FixGrammar => {
+                "Fix spelling, grammar and punctuation in the user's dictated text. \
+                 Keep the wording, meaning and language otherwise unchanged."
+            }
+            Preset::Concise => {
+                "Rewrite the user's dictated text to be clear and concise. \
+                 Keep every fact and the original language."
+            }
+            Preset::BulletList => {
+                "Turn the user's dictated text into a bullet list using \"- \" items, \
+                 one point per item. Keep the original language."
+            }
+            Preset::FormalEmail => {
+                "Rewrite the user's dictated text as a polite, formal email body. \
+                 Keep the original language and do not invent details."
+            }
+            Preset::Custom => "",
+        }
+    }
+}
+
+/// Appended to every instruction so chatty models return only the text.
+const OUTPUT_RULE: &str = "Reply with the rewritten text only, without quotes, comments or explanations.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PolishSettings {
+    pub enabled: bool,
+    /// Base URL of the API, e.g. `http://127.0.0.1:11434/v1`.
+    pub endpoint: String,
+    pub model: String,
+    /// Sent as a bearer token when the local server requires one. Stored in
+    /// the system keychain.
+    pub api_key: Option<String>,
+    pub preset: Preset,
+    pub custom_instruction: String,
+    pub timeout_ms: u64,
+    pub temperature: f32,
+}
+
+impl Default for PolishSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://127.0.0.1:11434/v1".to_string(),
+            model: String::new(),
+            api_key: None,
+            preset: Preset::FixGrammar,
+            custom_instruction: String::new(),
+            timeout_ms: 8000,
+            temperature: 0.2,
+        }
+    }
+}
+
+impl PolishSettings {
+    fn instruction(&self) -> String {
+        let base = match self.preset {
+            Preset::Custom => self.custom_instruction.trim(),
+            preset => preset.instruction(),
+        };
+        format!("{} {}", base, OUTPUT_RULE)
+    }
+}
+
+/// How a history entry's text was polished.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Polish {
+    /// Text before the rewrite, after the post-processing pipeline.
+    pub original_text: String,
+    pub preset: Preset,
+    pub model: String,
+    pub processing_time_ms: u64,
+}
+
+/// Settings as shown in the settings window, API key included.
+pub fn load_settings(app: &AppHandle) -> PolishSettings {
+    PolishSettings { api_key: load_api_key(), ..stored_settings(app) }
+}
+
+/// Settings from settings.json, without the keychain lookup.
+fn stored_settings(app: &AppHandle) -> PolishSettings {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &AppHandle, settings: &PolishSettings) -> Result<()> {
+    validate(settings)?;
+    store_api_key(settings.api_key.as_deref())?;
+    let stored = PolishSettings { api_key: None, ..settings.clone() };
+    let store = app.store("settings.json")?;
+    store.set(SETTINGS_KEY, serde_json::to_value(stored)?);
+    Ok(())
+}
+
+fn keychain_entry() -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT).context("Failed to open the system keychain")
+}
+
+fn load_api_key() -> Option<String> {
+    let entry = keychain_entry().ok()?;
+    match entry.get_password() {
+        Ok(key) => Some(key),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            eprintln!("[audioshift] Failed to read the polish API key from the keychain: {}", e);
+            None
+        }
+    }
+}
+
+/// Save `key` to the keychain, or remove it when there's none.
+fn store_api_key(key: Option<&str>) -> Result<()> {
+    let entry = keychain_entry()?;
+    match key.filter(|k| !k.is_empty()) {
+        Some(key) => entry
+            .set_password(key)
+            .context("Failed to store the API key in the system keychain"),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("Failed to remove the API key from the system keychain"),
+        },
+    }
+}
+
+fn validate(settings: &PolishSettings) -> Result<()> {
+    completions_url(&settings.endpoint)?;
+    anyhow::ensure!(settings.timeout_ms > 0, "Timeout must be positive");
+    if settings.enabled {
+        anyhow::ensure!(!settings.model.trim().is_empty(), "Choose a model for the polish step");
+        anyhow::ensure!(
+            settings.preset != Preset::Custom || !settings.custom_instruction.trim().is_empty(),
+            "The custom preset needs an instruction"
+        );
+    }
+    Ok(())
+}
+
+/// `/chat/completions` under `endpoint`, which must be a loopback address.
+fn completions_url(endpoint: &str) -> Result<reqwest::Url> {
+    let base = endpoint.trim().trim_end_matches('/');
+    let base = base.strip_suffix("/chat/completions").unwrap_or(base);
+    let url = reqwest::Url::parse(&format!("{}/chat/completions", base))
+        .with_context(|| format!("Invalid endpoint URL: {}", endpoint))?;
+    anyhow::ensure!(
+        matches!(url.scheme(), "http" | "https"),
+        "Endpoint must be an http(s) URL: {}",
+        endpoint
+    );
+    let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    let local = host.eq_ignore_ascii_case("localhost")
+        || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback());
+    anyhow::ensure!(local, "Endpoint must be on this machine (localhost): {}", endpoint);
+    Ok(url)
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    content: Option<String>,
+}
+
+/// Send `text` to the endpoint and return the rewrite.
+pub async fn rewrite(settings: &PolishSettings, text: &str) -> Result<String> {
+    let url = completions_url(&settings.endpoint)?;
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .timeout(Duration::from_millis(settings.timeout_ms))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let instruction = settings.instruction();
+    let body = ChatRequest {
+        model: &settings.model,
+        messages: [
+            ChatMessage { role: "system", content: &instruction },
+            ChatMessage { role: "user", content: text },
+        ],
+        temperature: settings.temperature,
+        stream: false,
+    };
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(&body)?);
+    if let Some(key) = settings.api_key.as_deref().filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(key);
+    }
+
+    let resp = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            anyhow::anyhow!("Polish endpoint timed out after {}ms", settings.timeout_ms)
+        } else {
+            anyhow::anyhow!("Polish endpoint unreachable: {}", e)
+        }
+    })?;
+    let status = resp.status();
+    let bytes = resp.bytes().await.context("Failed to read polish response")?;
+    anyhow::ensure!(
+        status.is_success(),
+        "Polish endpoint returned {}: {}",
+        status,
+        String::from_utf8_lossy(&bytes).chars().take(200).collect::<String>()
+    );
+    let parsed: ChatResponse = serde_json::from_slice(&bytes).context("Unexpected polish response")?;
+    let content = parsed
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|c| c.message.content)
+        .unwrap_or_default();
+    let content = content.trim();
+    anyhow::ensure!(!content.is_empty(), "Polish endpoint returned no text");
+    Ok(content.to_string())
+}
+
+/// Polish `text` if enabled. On failure the error is logged, reported via
+/// the `polish-failed` event, and `text` is returned unchanged.
+pub async fn apply(app: &AppHandle, text: &str) -> (String, Option<Polish>) {
+    let settings = stored_settings(app);
+    if !settings.enabled || text.trim().is_empty() {
+        return (text.to_string(), None);
+    }
+    let settings = PolishSettings { api_key: load_api_key(), ..settings };
+    let (text, polish, error) = polish_or_original(settings, text).await;
+    if let Some(e) = error {
+        eprintln!("[audioshift] Polish failed, using unpolished text: {}", e);
+        let _ = app.emit("polish-failed", e.to_string());
+    }
+    (text, polish)
+}
+
+/// The rewrite of `text`, or `text` itself and the reason the rewrite failed.
+/// `text` may hold snippet markers (see [`crate::postprocess::Pipeline::run_marked`]);
+/// a rewrite that drops or repeats one is rejected.
+async fn polish_or_original(
+    settings: PolishSettings,
+    text: &str,
+) -> (String, Option<Polish>, Option<anyhow::Error>) {
+    let started = std::time::Instant::now();
+    let rewritten = rewrite(&settings, text).await.and_then(|polished| {
+        anyhow::ensure!(
+            snippets::markers(&polished) == snippets::markers(text),
+            "Polish endpoint changed the inserted snippets"
+        );
+        Ok(polished)
+    });
+    match rewritten {
+        Ok(polished) => {
+            let info = Polish {
+                original_text: text.to_string(),
+                preset: settings.preset,
+                model: settings.model,
+                processing_time_ms: started.elapsed().as_millis() as u64,
+            };
+            (polished, Some(info), None)
+        }
+        Err(e) => (text.to_string(), None, Some(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Answer one request on a loopback port with `response`, after `delay`.
+    /// Returns the endpoint URL.
+    fn serve(response: String, delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            std::thread::sleep(delay);
+            let _ = stream.write_all(response.as_bytes());
+        });
+        endpoint
+    }
+
+    fn response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    fn settings(endpoint: String) -> PolishSettings {
+        PolishSettings {
+            enabled: true,
+            endpoint,
+            model: "stub".to_string(),
+            timeout_ms: 500,
+            ..Default::default()
+        }
+    }
+
+    fn polish(settings: PolishSettings, text: &str) -> (String, Option<Polish>, Option<anyhow::Error>) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(polish_or_original(settings, text))
+    }
+
+    #[test]
+    fn returns_the_rewrite() {
+        let body = r#"{"choices":[{"message":{"role":"assistant","content":" Fixed text. "}}]}"#;
+        let endpoint = serve(response("200 OK", body), Duration::ZERO);
+
+        let (text, info, error) = polish(settings(endpoint), "fixed text");
+        assert!(error.is_none());
+        assert_eq!(text, "Fixed text.");
+        assert_eq!(info.unwrap().original_text, "fixed text");
+    }
+
+    #[test]
+    fn falls_back_on_timeout() {
+        let body = r#"{"choices":[{"message":{"content":"too late"}}]}"#;
+        let endpoint = serve(response("200 OK", body), Duration::from_secs(2));
+
+        let (text, info, error) = polish(settings(endpoint), "original text");
+        assert_eq!(text, "original text");
+        assert!(info.is_none());
+        assert!(error.unwrap().to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn falls_back_on_error_status() {
+        let endpoint = serve(response("500 Internal Server Error", r#"{"error":"boom"}"#), Duration::ZERO);
+
+        let (text, info, error) = polish(settings(endpoint), "original text");
+        assert_eq!(text, "original text");
+        assert!(info.is_none());
+        assert!(error.unwrap().to_string().contains("500"));
+    }
+
+    #[test]
+    fn falls_back_when_snippets_change() {
+        let body = r#"{"choices":[{"message":{"content":"Thanks, best regards."}}]}"#;
+        let endpoint = serve(response("200 OK", body), Duration::ZERO);
+
+        let (text, info, error) = polish(settings(endpoint), "thanks \u{F0000}");
+        assert_eq!(text, "thanks \u{F0000}");
+        assert!(info.is_none());
+        assert!(error.unwrap().to_string().contains("snippets"));
+    }
+}
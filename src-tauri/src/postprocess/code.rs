@@ -0,0 +1,300 @@
+//! Code dictation: "camel case user id" becomes `userId`, "open paren" and
+//! "arrow" become symbols, and the engine's capitalization and sentence
+//! punctuation are dropped. Commands are English whatever the spoken language.
+
+use super::words::{tokenize, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Camel,
+    Pascal,
+    Snake,
+    Kebab,
+    Screaming,
+}
+
+const CASES: &[(&str, Case)] = &[
+    ("screaming snake case", Case::Screaming),
+    ("screaming case", Case::Screaming),
+    ("constant case", Case::Screaming),
+    ("camel case", Case::Camel),
+    ("pascal case", Case::Pascal),
+    ("snake case", Case::Snake),
+    ("kebab case", Case::Kebab),
+];
+
+/// Ends a casing command early: "snake case max retries end case is five".
+const END_CASE: &str = "end case";
+
+/// How a symbol joins its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Attach {
+    /// Spaces on both sides: `=`, `->`.
+    Spaced,
+    /// No space after; no space before a word or closing symbol: `(`, `[`.
+    Open,
+    /// No space before: `)`, `]`.
+    Close,
+    /// No space on either side: `.`, `::`.
+    Tight,
+    /// No space before, space after: `,`, `;`.
+    Trailing,
+    /// Space before, none after: `!`, `&`, `{`.
+    Prefix,
+}
+
+/// Spoken symbols, longest phrases first so "double equals" wins over "equals".
+const SYMBOLS: &[(&str, &str, Attach)] = &[
+    ("triple equals", "===", Attach::Spaced),
+    ("double equals", "==", Attach::Spaced),
+    ("not equals", "!=", Attach::Spaced),
+    ("greater than or equal to", ">=", Attach::Spaced),
+    ("less than or equal to", "<=", Attach::Spaced),
+    ("greater than", ">", Attach::Spaced),
+    ("less than", "<", Attach::Spaced),
+    ("plus equals", "+=", Attach::Spaced),
+    ("minus equals", "-=", Attach::Spaced),
+    ("fat arrow", "=>", Attach::Spaced),
+    ("arrow", "->", Attach::Spaced),
+    ("equals", "=", Attach::Spaced),
+    ("plus plus", "++", Attach::Tight),
+    ("plus", "+", Attach::Spaced),
+    ("minus", "-", Attach::Spaced),
+    ("times", "*", Attach::Spaced),
+    ("divided by", "/", Attach::Spaced),
+    ("modulo", "%", Attach::Spaced),
+    ("and and", "&&", Attach::Spaced),
+    ("or or", "||", Attach::Spaced),
+    ("pipe", "|", Attach::Spaced),
+    ("open paren", "(", Attach::Open),
+    ("close paren", ")", Attach::Close),
+    ("open bracket", "[", Attach::Open),
+    ("close bracket", "]", Attach::Close),
+    ("open brace", "{", Attach::Prefix),
+    ("close brace", "}", Attach::Close),
+    ("open curly", "{", Attach::Prefix),
+    ("close curly", "}", Attach::Close),
+    ("open angle", "<", Attach::Open),
+    ("close angle", ">", Attach::Close),
+    ("open quote", "\"", Attach::Open),
+    ("close quote", "\"", Attach::Close),
+    ("single quote", "'", Attach::Tight),
+    ("backtick", "`", Attach::Tight),
+    ("double colon", "::", Attach::Tight),
+    ("dot", ".", Attach::Tight),
+    ("underscore", "_", Attach::Tight),
+    ("dash", "-", Attach::Tight),
+    ("slash", "/", Attach::Tight),
+    ("backslash", "\\", Attach::Tight),
+    ("comma", ",", Attach::Trailing),
+    ("semicolon", ";", Attach::Trailing),
+    ("colon", ":", Attach::Trailing),
+    ("question mark", "?", Attach::Close),
+    ("bang", "!", Attach::Prefix),
+    ("ampersand", "&", Attach::Prefix),
+    ("star", "*", Attach::Prefix),
+    ("hash", "#", Attach::Prefix),
+    ("at sign", "@", Attach::Prefix),
+    ("dollar sign", "$", Attach::Prefix),
+    ("new line", "\n", Attach::Tight),
+    ("newline", "\n", Attach::Tight),
+];
+
+/// Digits said on their own; larger numbers come from inverse text
+/// normalization when it's enabled.
+const DIGITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+enum Piece {
+    Word(String),
+    Symbol(&'static str, Attach),
+}
+
+/// Punctuation engines add on their own; code gets it from spoken symbols.
+const ENGINE_PUNCT: [char; 6] = ['.', ',', '!', '?', ';', ':'];
+
+/// Length of `phrase` if the words at `words[i..]` spell it.
+fn match_phrase(words: &[Word], i: usize, phrase: &str) -> Option<usize> {
+    let parts: Vec<&str> = phrase.split(' ').collect();
+    let fits = parts.len() <= words.len() - i
+        && parts.iter().enumerate().all(|(k, p)| words[i + k].key() == *p);
+    fits.then_some(parts.len())
+}
+
+fn match_symbol(words: &[Word], i: usize) -> Option<(usize, &'static str, Attach)> {
+    SYMBOLS
+        .iter()
+        .find_map(|(phrase, symbol, attach)| match_phrase(words, i, phrase).map(|n| (n, *symbol, *attach)))
+}
+
+fn match_case(words: &[Word], i: usize) -> Option<(usize, Case)> {
+    CASES
+        .iter()
+        .find_map(|(phrase, case)| match_phrase(words, i, phrase).map(|n| (n, *case)))
+}
+
+fn apply_case(case: Case, parts: &[String]) -> String {
+    let lower: Vec<String> = parts.iter().map(|p| p.to_lowercase()).collect();
+    let title = |w: &String| {
+        let mut chars = w.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+    match case {
+        Case::Camel => lower
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.clone() } else { title(w) })
+            .collect(),
+        Case::Pascal => lower.iter().map(title).collect(),
+        Case::Snake => lower.join("_"),
+        Case::Kebab => lower.join("-"),
+        Case::Screaming => lower.join("_").to_uppercase(),
+    }
+}
+
+/// Drop the engine's sentence punctuation and its capitalization of
+/// sentence starts. Words cased any other way ("JSON", "iOS") are kept.
+fn strip_prose(words: Vec<Word>) -> Vec<Word> {
+    let mut sentence_start = true;
+    words
+        .into_iter()
+        .map(|mut word| {
+            let ends_sentence = word.ends_sentence();
+            let trimmed = word.text.trim_end_matches(ENGINE_PUNCT).len();
+            if trimmed > 0 {
+                word.text.truncate(trimmed);
+            }
+            let core = word.core();
+            let capitalized = word.starts_upper() && core.chars().skip(1).all(|c| !c.is_uppercase());
+            if sentence_start && capitalized && core.chars().count() > 1 {
+                word.text = word.text.to_lowercase();
+            }
+            sentence_start = ends_sentence;
+            word
+        })
+        .collect()
+}
+
+pub fn apply(text: &str) -> String {
+    let (_, words) = tokenize(text);
+    // An engine comma or full stop ends a casing run
+    let boundaries: Vec<bool> = words
+        .iter()
+        .map(|w| w.text.ends_with(ENGINE_PUNCT) || w.sep.contains('\n'))
+        .collect();
+    let words = strip_prose(words);
+
+    let mut pieces = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let start = i;
+        i = next_piece(&words, &boundaries, i, &mut pieces);
+        // Keep line breaks the engine put between words
+        if words[start..i].last().is_some_and(|w| w.sep.contains('\n')) {
+            pieces.push(Piece::Symbol("\n", Attach::Tight));
+        }
+    }
+
+    render(&pieces)
+}
+
+/// Push the piece starting at `words[i]` and return the index after it.
+fn next_piece(words: &[Word], boundaries: &[bool], i: usize, pieces: &mut Vec<Piece>) -> usize {
+    if let Some((n, case)) = match_case(words, i) {
+        let mut parts = Vec::new();
+        let mut j = i + n;
+        while j < words.len() && !boundaries[j - 1] {
+            if match_phrase(words, j, END_CASE).is_some() {
+                j += 2;
+                break;
+            }
+            if match_symbol(words, j).is_some() || match_case(words, j).is_some() {
+                break;
+            }
+            parts.push(words[j].core().to_string());
+            j += 1;
+        }
+        if parts.is_empty() {
+            pieces.push(Piece::Word(words[i].text.clone()));
+            return i + 1;
+        }
+        pieces.push(Piece::Word(apply_case(case, &parts)));
+        return j;
+    }
+    if let Some((n, symbol, attach)) = match_symbol(words, i) {
+        pieces.push(Piece::Symbol(symbol, attach));
+        return i + n;
+    }
+    let word = match DIGITS.iter().position(|d| *d == words[i].key()) {
+        Some(digit) => digit.to_string(),
+        None => words[i].text.clone(),
+    };
+    pieces.push(Piece::Word(word));
+    i + 1
+}
+
+fn render(pieces: &[Piece]) -> String {
+    let mut out = String::new();
+    let mut prev: Option<&Piece> = None;
+    for piece in pieces {
+        let glue_left = match piece {
+            Piece::Word(_) => false,
+            Piece::Symbol(_, Attach::Open) => matches!(prev, Some(Piece::Word(_) | Piece::Symbol(_, Attach::Close))),
+            Piece::Symbol(_, attach) => matches!(attach, Attach::Close | Attach::Tight | Attach::Trailing),
+        };
+        let prev_glues_right = match prev {
+            None => true,
+            Some(Piece::Word(_)) => false,
+            Some(Piece::Symbol(s, attach)) => {
+                *s == "\n" || matches!(attach, Attach::Open | Attach::Tight | Attach::Prefix)
+            }
+        };
+        if !glue_left && !prev_glues_right {
+            out.push(' ');
+        }
+        match piece {
+            Piece::Word(w) => out.push_str(w),
+            Piece::Symbol(s, _) => out.push_str(s),
+        }
+        prev = Some(piece);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casing_commands() {
+        for (input, expected) in [
+            ("camel case user id", "userId"),
+            ("Pascal case http client.", "HttpClient"),
+            ("snake case max retries end case equals three", "max_retries = 3"),
+            ("screaming snake case max retries", "MAX_RETRIES"),
+            ("kebab case main menu", "main-menu"),
+        ] {
+            assert_eq!(apply(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn spoken_symbols() {
+        for (input, expected) in [
+            ("If x double equals y.", "if x == y"),
+            ("print open paren name comma age close paren semicolon", "print(name, age);"),
+            ("self dot items dot len open paren close paren", "self.items.len()"),
+            ("fn main open paren close paren arrow i32 open brace", "fn main() -> i32 {"),
+            ("bang done and and ready", "!done && ready"),
+        ] {
+            assert_eq!(apply(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn keeps_words_cased_on_purpose() {
+        assert_eq!(apply("Parse the JSON for iOS."), "parse the JSON for iOS");
+    }
+}
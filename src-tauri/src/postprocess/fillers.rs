@@ -0,0 +1,228 @@
+//! Filler-word removal and disfluency cleanup: drops "um", "uh", "you know"
+//! and collapses stutters like "I I think" or "th- the", then repairs the
+//! capitalization and punctuation left behind.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use super::words::{capitalize, join, tokenize, Word};
+
+const SETTINGS_KEY: &str = "fillerCleanup";
+
+/// Longest repeated phrase collapsed ("I think I think").
+const MAX_REPEAT_WORDS: usize = 3;
+
+/// Words that are legitimately doubled ("I know that that works").
+const ALLOWED_REPEATS: &[&str] = &["that", "had", "bye", "no", "very", "ha", "das", "que", "nous", "vous"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FillerSettings {
+    pub enabled: bool,
+    /// Collapse stutters and repeated words.
+    pub collapse_repeats: bool,
+    /// Filler words and phrases per language code, replacing the built-in
+    /// list for that language.
+    pub words: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for FillerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            collapse_repeats: true,
+            words: BTreeMap::new(),
+        }
+    }
+}
+
+pub fn load_settings(app: &AppHandle) -> FillerSettings {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &AppHandle, settings: &FillerSettings) -> anyhow::Result<()> {
+    let store = app.store("settings.json")?;
+    store.set(SETTINGS_KEY, serde_json::to_value(settings)?);
+    Ok(())
+}
+
+/// Built-in fillers for `language`. Only sounds and set phrases that are
+/// almost never meant literally; "like" or "so" need a human to judge.
+pub fn default_fillers(language: &str) -> &'static [&'static str] {
+    match language {
+        "en" => &["um", "umm", "uh", "uhh", "uhm", "er", "erm", "hmm", "mm", "you know", "I mean"],
+        "de" => &["äh", "ähm", "öh", "öhm", "hm", "hmm"],
+        "fr" => &["euh", "heu", "hum", "bah"],
+        "es" => &["eh", "ehm", "em", "mmm", "o sea"],
+        "it" => &["ehm", "uhm", "cioè"],
+        "nl" => &["eh", "ehm", "uh", "uhm"],
+        // Not "é": it's also "is", one of the most common words
+        "pt" => &["hum", "tipo assim"],
+        _ => &[],
+    }
+}
+
+/// Length of the filler phrase starting at `words[0]`, if any. Multi-word
+/// fillers only count when set off by punctuation on both sides, so
+/// "do you know him" and "I mean it" survive while "it's, you know, fine"
+/// doesn't.
+fn match_filler(prev: Option<&Word>, words: &[Word], fillers: &[Vec<String>]) -> Option<usize> {
+    fillers
+        .iter()
+        .filter(|f| f.len() <= words.len())
+        .filter(|f| f.iter().zip(words).all(|(f, w)| *f == w.key()))
+        .filter(|f| {
+            f.len() == 1 || {
+                let before = prev.is_none_or(|p| !p.trailing_punct().is_empty() || p.sep.contains('\n'));
+                let after = !words[f.len() - 1].trailing_punct().is_empty() || f.len() == words.len();
+                before && after
+            }
+        })
+        .map(|f| f.len())
+        .max()
+}
+
+fn remove_fillers(words: Vec<Word>, fillers: &[Vec<String>]) -> Vec<Word> {
+    let mut out: Vec<Word> = Vec::with_capacity(words.len());
+    let mut capitalize_next = false;
+    let mut i = 0;
+    while i < words.len() {
+        if let Some(n) = match_filler(out.last(), &words[i..], fillers) {
+            let starts_sentence = out.last().is_none_or(Word::ends_sentence);
+            capitalize_next |= starts_sentence && words[i].starts_upper();
+            let last = &words[i + n - 1];
+            if let Some(prev) = out.last_mut() {
+                let punct = last.trailing_punct();
+                if punct.contains(['.', '!', '?']) && !prev.ends_sentence() {
+                    // "I think uh." keeps its full stop
+                    let trimmed = prev.text.trim_end_matches([',', ';', ':']).len();
+                    prev.text.truncate(trimmed);
+                    prev.text.push_str(punct.trim_start_matches([',', ';', ':']));
+                } else if punct.starts_with(',') && prev.text.ends_with(',') {
+                    // "we should, uh, go" reads as "we should go"
+                    prev.text.pop();
+                }
+                let at_end = i + n == words.len();
+                if at_end || (last.sep.contains('\n') && !prev.sep.contains('\n')) {
+                    prev.sep = last.sep.clone();
+                }
+            }
+            i += n;
+            continue;
+        }
+        let mut word = words[i].clone();
+        if capitalize_next {
+            capitalize(&mut word);
+            capitalize_next = false;
+        }
+        out.push(word);
+        i += 1;
+    }
+    out
+}
+
+/// Whether `words[i..i + n]` is repeated right after itself.
+fn is_repeat(words: &[Word], i: usize, n: usize) -> bool {
+    if i + 2 * n > words.len() {
+        return false;
+    }
+    // A sentence boundary between the copies makes the repeat intentional
+    if words[i + n - 1].ends_sentence() {
+        return false;
+    }
+    if n == 1 && ALLOWED_REPEATS.contains(&words[i].key().as_str()) {
+        return false;
+    }
+    (0..n).all(|k| !words[i + k].key().is_empty() && words[i + k].key() == words[i + n + k].key())
+}
+
+/// "th- the": a cut-off word followed by the word it started.
+fn is_false_start(words: &[Word], i: usize) -> bool {
+    let Some(next) = words.get(i + 1) else {
+        return false;
+    };
+    let word = &words[i];
+    let key = word.key();
+    word.text.ends_with('-') && !key.is_empty() && next.key().starts_with(&key) && next.key() != key
+}
+
+fn collapse_repeats(mut words: Vec<Word>) -> Vec<Word> {
+    let mut i = 0;
+    while i < words.len() {
+        let repeat = (1..=MAX_REPEAT_WORDS)
+            .rev()
+            .find(|&n| is_repeat(&words, i, n))
+            .or_else(|| is_false_start(&words, i).then_some(1));
+        match repeat {
+            Some(n) => {
+                let upper = words[i].starts_upper();
+                words.drain(i..i + n);
+                if upper {
+                    capitalize(&mut words[i]);
+                }
+            }
+            None => i += 1,
+        }
+    }
+    words
+}
+
+pub fn apply(settings: &FillerSettings, language: Option<&str>, text: &str) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let language = language.unwrap_or("en");
+    let fillers: Vec<Vec<String>> = match settings.words.get(language) {
+        Some(words) => words.iter().map(String::as_str).map(split_phrase).collect(),
+        None => default_fillers(language).iter().copied().map(split_phrase).collect(),
+    };
+
+    let (leading, mut words) = tokenize(text);
+    if !fillers.is_empty() {
+        words = remove_fillers(words, &fillers);
+    }
+    if settings.collapse_repeats {
+        words = collapse_repeats(words);
+    }
+
+    join(leading, words)
+}
+
+fn split_phrase(phrase: &str) -> Vec<String> {
+    phrase.split_whitespace().map(str::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> FillerSettings {
+        FillerSettings { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn portuguese_sentences_survive() {
+        for text in [
+            "O café é muito bom.",
+            "É verdade, não é?",
+            "A casa é grande e o jardim é bonito.",
+            "Ele disse que é tipo assim mesmo.",
+        ] {
+            assert_eq!(apply(&enabled(), Some("pt"), text), text);
+        }
+    }
+
+    #[test]
+    fn portuguese_fillers_are_removed() {
+        assert_eq!(
+            apply(&enabled(), Some("pt"), "Hum, eu acho que, tipo assim, está certo."),
+            "Eu acho que está certo."
+        );
+    }
+}
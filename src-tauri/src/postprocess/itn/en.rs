@@ -0,0 +1,631 @@
+//! English inverse text normalization.
+//!
+//! Numbers from ten up become digits ("twenty five" -> "25"); one to nine
+//! stay words unless a unit, currency, percent sign or date needs the digit.
+//! Digits already written by the engine are only touched to attach units
+//! ("25 percent" -> "25%").
+
+use crate::postprocess::words::{join, tokenize, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+fn cardinal(key: &str) -> Option<(u64, Kind)> {
+    use Kind::*;
+    Some(match key {
+        "zero" => (0, Unit),
+        "one" => (1, Unit),
+        "two" => (2, Unit),
+        "three" => (3, Unit),
+        "four" => (4, Unit),
+        "five" => (5, Unit),
+        "six" => (6, Unit),
+        "seven" => (7, Unit),
+        "eight" => (8, Unit),
+        "nine" => (9, Unit),
+        "ten" => (10, Teen),
+        "eleven" => (11, Teen),
+        "twelve" => (12, Teen),
+        "thirteen" => (13, Teen),
+        "fourteen" => (14, Teen),
+        "fifteen" => (15, Teen),
+        "sixteen" => (16, Teen),
+        "seventeen" => (17, Teen),
+        "eighteen" => (18, Teen),
+        "nineteen" => (19, Teen),
+        "twenty" => (20, Tens),
+        "thirty" => (30, Tens),
+        "forty" => (40, Tens),
+        "fifty" => (50, Tens),
+        "sixty" => (60, Tens),
+        "seventy" => (70, Tens),
+        "eighty" => (80, Tens),
+        "ninety" => (90, Tens),
+        "hundred" => (100, Hundred),
+        "thousand" => (1_000, Scale),
+        "million" => (1_000_000, Scale),
+        "billion" => (1_000_000_000, Scale),
+        "trillion" => (1_000_000_000_000, Scale),
+        _ => return None,
+    })
+}
+
+fn ordinal(key: &str) -> Option<(u64, Kind)> {
+    use Kind::*;
+    Some(match key {
+        "first" => (1, Unit),
+        "second" => (2, Unit),
+        "third" => (3, Unit),
+        "fourth" => (4, Unit),
+        "fifth" => (5, Unit),
+        "sixth" => (6, Unit),
+        "seventh" => (7, Unit),
+        "eighth" => (8, Unit),
+        "ninth" => (9, Unit),
+        "tenth" => (10, Teen),
+        "eleventh" => (11, Teen),
+        "twelfth" => (12, Teen),
+        "thirteenth" => (13, Teen),
+        "fourteenth" => (14, Teen),
+        "fifteenth" => (15, Teen),
+        "sixteenth" => (16, Teen),
+        "seventeenth" => (17, Teen),
+        "eighteenth" => (18, Teen),
+        "nineteenth" => (19, Teen),
+        "twentieth" => (20, Tens),
+        "thirtieth" => (30, Tens),
+        "fortieth" => (40, Tens),
+        "fiftieth" => (50, Tens),
+        "sixtieth" => (60, Tens),
+        "seventieth" => (70, Tens),
+        "eightieth" => (80, Tens),
+        "ninetieth" => (90, Tens),
+        "hundredth" => (100, Hundred),
+        "thousandth" => (1_000, Scale),
+        "millionth" => (1_000_000, Scale),
+        "billionth" => (1_000_000_000, Scale),
+        _ => return None,
+    })
+}
+
+/// A single spoken digit, as in "two point five" or "three oh five".
+fn digit(key: &str) -> Option<char> {
+    match key {
+        "zero" | "oh" | "o" => Some('0'),
+        _ => match cardinal(key) {
+            Some((v @ 1..=9, Kind::Unit)) => char::from_digit(v as u32, 10),
+            _ => None,
+        },
+    }
+}
+
+fn is_number_word(key: &str) -> bool {
+    cardinal(key).is_some() || ordinal(key).is_some()
+}
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+fn month(key: &str) -> Option<usize> {
+    MONTHS.iter().position(|m| *m == key)
+}
+
+/// Months that are also common verbs ("you may first", "we march").
+const VERB_MONTHS: [&str; 2] = ["may", "march"];
+
+/// Spoken units and their symbols, longest phrases first so
+/// "kilometers per hour" wins over "kilometers".
+const UNITS: &[(&[&str], &str)] = &[
+    (&["kilometers", "per", "hour"], "km/h"),
+    (&["kilometres", "per", "hour"], "km/h"),
+    (&["miles", "per", "hour"], "mph"),
+    (&["degrees", "celsius"], "°C"),
+    (&["degrees", "centigrade"], "°C"),
+    (&["degrees", "fahrenheit"], "°F"),
+    (&["degree", "celsius"], "°C"),
+    (&["degree", "fahrenheit"], "°F"),
+    (&["degrees"], "°"),
+    (&["degree"], "°"),
+    (&["kilometers"], "km"),
+    (&["kilometer"], "km"),
+    (&["kilometres"], "km"),
+    (&["kilometre"], "km"),
+    (&["meters"], "m"),
+    (&["meter"], "m"),
+    (&["metres"], "m"),
+    (&["metre"], "m"),
+    (&["centimeters"], "cm"),
+    (&["centimeter"], "cm"),
+    (&["centimetres"], "cm"),
+    (&["millimeters"], "mm"),
+    (&["millimeter"], "mm"),
+    (&["millimetres"], "mm"),
+    (&["kilograms"], "kg"),
+    (&["kilogram"], "kg"),
+    (&["grams"], "g"),
+    (&["gram"], "g"),
+    (&["milligrams"], "mg"),
+    (&["liters"], "L"),
+    (&["liter"], "L"),
+    (&["litres"], "L"),
+    (&["litre"], "L"),
+    (&["milliliters"], "mL"),
+    (&["millilitres"], "mL"),
+    (&["kilobytes"], "KB"),
+    (&["megabytes"], "MB"),
+    (&["gigabytes"], "GB"),
+    (&["terabytes"], "TB"),
+    (&["milliseconds"], "ms"),
+    (&["kilohertz"], "kHz"),
+    (&["megahertz"], "MHz"),
+    (&["gigahertz"], "GHz"),
+    (&["kilowatts"], "kW"),
+    (&["watts"], "W"),
+];
+
+const CURRENCIES: &[(&str, &str)] = &[
+    ("dollars", "$"),
+    ("dollar", "$"),
+    ("bucks", "$"),
+    ("euros", "€"),
+    ("euro", "€"),
+    ("yen", "¥"),
+];
+
+/// A parsed number spanning `len` words from where parsing started.
+#[derive(Debug, Clone)]
+struct Number {
+    int: u64,
+    frac: Option<String>,
+    ordinal: bool,
+    /// Written with digits by the engine rather than spoken as words.
+    digits: bool,
+    len: usize,
+}
+
+impl Number {
+    /// "one" through "nine" on their own, which read better as words.
+    fn is_small_word(&self) -> bool {
+        !self.digits && self.len == 1 && self.int < 10 && self.frac.is_none()
+    }
+
+    fn format(&self) -> String {
+        let mut out = format_int(self.int);
+        if let Some(frac) = &self.frac {
+            out.push('.');
+            out.push_str(frac);
+        }
+        if self.ordinal {
+            out.push_str(ordinal_suffix(self.int));
+        }
+        out
+    }
+}
+
+fn format_int(n: u64) -> String {
+    let digits = n.to_string();
+    if n < 10_000 {
+        return digits;
+    }
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn ordinal_suffix(n: u64) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Whether a number may continue past `word`: punctuation or a line break
+/// after it ends the number.
+fn continues(word: &Word) -> bool {
+    word.trailing_punct().is_empty() && !word.sep.contains('\n')
+}
+
+fn key_at(words: &[Word], i: usize) -> String {
+    words.get(i).map(|w| w.key().replace('’', "'")).unwrap_or_default()
+}
+
+/// "25", "3.5", "1,000" or "3rd" written by the engine.
+fn parse_digits(word: &Word) -> Option<Number> {
+    let core = word.core();
+    let (num, ordinal) = match ["st", "nd", "rd", "th"].iter().find_map(|s| core.strip_suffix(s)) {
+        Some(num) => (num, true),
+        None => (core, false),
+    };
+    if num.is_empty() || !num.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.') {
+        return None;
+    }
+    let plain = num.replace(',', "");
+    let (int, frac) = match plain.split_once('.') {
+        Some((int, frac)) => (int.parse().ok()?, Some(frac.to_string())),
+        None => (plain.parse().ok()?, None),
+    };
+    Some(Number { int, frac, ordinal, digits: true, len: 1 })
+}
+
+/// Cardinal or ordinal number words from `words[start]`, e.g. "two hundred
+/// and five", "twenty first", "three point one four".
+fn parse_number(words: &[Word], start: usize) -> Option<Number> {
+    if let Some(num) = words.get(start).and_then(parse_digits) {
+        return Some(num);
+    }
+
+    let mut total = 0u64;
+    let mut current = 0u64;
+    let mut last: Option<Kind> = None;
+    let mut last_scale = u64::MAX;
+    let mut is_ordinal = false;
+    let mut end = start;
+    let mut i = start;
+
+    while let Some(word) = words.get(i) {
+        let key = key_at(words, i);
+        let next_key = key_at(words, i + 1);
+        // "one hundred and five"
+        if key == "and"
+            && matches!(last, Some(Kind::Hundred | Kind::Scale))
+            && continues(&words[i - 1])
+            && continues(word)
+            && cardinal(&next_key).is_some_and(|(_, k)| matches!(k, Kind::Unit | Kind::Teen | Kind::Tens))
+        {
+            i += 1;
+            continue;
+        }
+        // "a hundred", "a million"
+        if key == "a"
+            && last.is_none()
+            && continues(word)
+            && cardinal(&next_key).is_some_and(|(_, k)| matches!(k, Kind::Hundred | Kind::Scale))
+        {
+            current = 1;
+            last = Some(Kind::Unit);
+            i += 1;
+            continue;
+        }
+
+        let (value, kind, ord) = match (cardinal(&key), ordinal(&key)) {
+            (Some((v, k)), _) => (v, k, false),
+            (None, Some((v, k))) => (v, k, true),
+            (None, None) => break,
+        };
+        let fits = match kind {
+            Kind::Unit if value == 0 => last.is_none(),
+            Kind::Unit => matches!(last, None | Some(Kind::Tens | Kind::Hundred | Kind::Scale)),
+            Kind::Teen | Kind::Tens => matches!(last, None | Some(Kind::Hundred | Kind::Scale)),
+            Kind::Hundred => matches!(last, None | Some(Kind::Unit | Kind::Teen | Kind::Tens)) && current < 100,
+            Kind::Scale => {
+                matches!(last, Some(Kind::Unit | Kind::Teen | Kind::Tens | Kind::Hundred)) && value < last_scale
+            }
+        };
+        if !fits {
+            break;
+        }
+        match kind {
+            Kind::Unit | Kind::Teen | Kind::Tens => current += value,
+            Kind::Hundred => current = current.max(1) * 100,
+            Kind::Scale => {
+                total += current * value;
+                current = 0;
+                last_scale = value;
+            }
+        }
+        last = Some(kind);
+        i += 1;
+        end = i;
+        if ord {
+            is_ordinal = true;
+            break;
+        }
+        if !continues(word) {
+            break;
+        }
+    }
+    if end == start {
+        return None;
+    }
+
+    let mut num = Number {
+        int: total + current,
+        frac: None,
+        ordinal: is_ordinal,
+        digits: false,
+        len: end - start,
+    };
+
+    // "two point five"
+    if !is_ordinal && continues(&words[end - 1]) && key_at(words, end) == "point" && continues(&words[end]) {
+        let mut frac = String::new();
+        let mut j = end + 1;
+        while let Some(d) = digit(&key_at(words, j)) {
+            frac.push(d);
+            j += 1;
+            if !continues(&words[j - 1]) {
+                break;
+            }
+        }
+        if !frac.is_empty() {
+            num.frac = Some(frac);
+            num.len = j - start;
+        }
+    }
+    Some(num)
+}
+
+/// Two-digit group: "fifteen", "forty", "forty five".
+fn parse_two_digit(words: &[Word], i: usize) -> Option<(u64, usize)> {
+    match cardinal(&key_at(words, i))? {
+        (v, Kind::Teen) => Some((v, 1)),
+        (v, Kind::Tens) => match cardinal(&key_at(words, i + 1)) {
+            Some((u @ 1..=9, Kind::Unit)) if continues(&words[i]) => Some((v + u, 2)),
+            _ => Some((v, 1)),
+        },
+        _ => None,
+    }
+}
+
+/// Years said in pairs: "nineteen ninety nine", "twenty twenty four",
+/// "nineteen oh five", "nineteen hundred". Outside dates only centuries
+/// 19 and 20 count, so "fifteen twenty" stays a pair of numbers.
+fn parse_year(words: &[Word], i: usize, in_date: bool) -> Option<(u64, usize)> {
+    let (century, n) = parse_two_digit(words, i)?;
+    let range = if in_date { 11..=20 } else { 19..=20 };
+    if !range.contains(&century) || !continues(&words[i + n - 1]) {
+        return None;
+    }
+    let j = i + n;
+    let key = key_at(words, j);
+    if key == "hundred" {
+        return Some((century * 100, n + 1));
+    }
+    if key == "oh" || key == "o" {
+        if let Some((u @ 1..=9, Kind::Unit)) = cardinal(&key_at(words, j + 1)) {
+            if continues(&words[j]) {
+                return Some((century * 100 + u, n + 2));
+            }
+        }
+        return None;
+    }
+    let (rest, m) = parse_two_digit(words, j)?;
+    Some((century * 100 + rest, n + m))
+}
+
+/// "three thirty pm", "ten am", "three o'clock", "at nine fifteen".
+fn parse_time(words: &[Word], i: usize) -> Option<(String, usize)> {
+    let hour = match cardinal(&key_at(words, i)) {
+        Some((h @ 1..=12, Kind::Unit | Kind::Teen)) => h,
+        _ => return None,
+    };
+    if !continues(&words[i]) {
+        return None;
+    }
+
+    let mut j = i + 1;
+    let mut minutes = None;
+    let key = key_at(words, j);
+    if key == "o'clock" {
+        return Some((format!("{} o'clock", hour), 2));
+    }
+    if key == "oh" || key == "o" {
+        if let Some((m @ 1..=9, Kind::Unit)) = cardinal(&key_at(words, j + 1)) {
+            if continues(&words[j]) {
+                minutes = Some(m);
+                j += 2;
+            }
+        }
+    } else if let Some((m @ 10..=59, n)) = parse_two_digit(words, j) {
+        minutes = Some(m);
+        j += n;
+    }
+
+    let meridiem = if minutes.is_none() || continues(&words[j - 1]) {
+        match key_at(words, j).replace('.', "").as_str() {
+            "am" => Some(("AM", 1)),
+            "pm" => Some(("PM", 1)),
+            k @ ("a" | "p") if key_at(words, j + 1) == "m" && continues(&words[j]) => {
+                Some((if k == "a" { "AM" } else { "PM" }, 2))
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let clock = match minutes {
+        Some(m) => format!("{}:{:02}", hour, m),
+        None => hour.to_string(),
+    };
+    match meridiem {
+        Some((suffix, n)) => Some((format!("{} {}", clock, suffix), j + n - i)),
+        // Without am/pm, "three thirty" is only a time after "at"
+        None if minutes.is_some()
+            && i > 0
+            && words[i - 1].key() == "at"
+            && continues(&words[i - 1]) =>
+        {
+            Some((clock, j - i))
+        }
+        None => None,
+    }
+}
+
+fn capitalized(key: &str) -> String {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// "march third twenty twenty four" -> "March 3, 2024";
+/// "the fifth of may" -> "the 5th of May".
+fn parse_date(words: &[Word], i: usize) -> Option<(String, usize)> {
+    if let Some(m) = month(&key_at(words, i)) {
+        if !continues(&words[i]) {
+            return None;
+        }
+        // "you may one day" isn't a date: a lowercase month needs an ordinal day
+        let day = parse_number(words, i + 1)
+            .filter(|d| (1..=31).contains(&d.int) && d.frac.is_none())
+            .filter(|d| d.ordinal || words[i].starts_upper())?;
+        let mut text = format!("{} {}", capitalized(MONTHS[m]), day.int);
+        let mut len = 1 + day.len;
+        let day_end = &words[i + len - 1];
+        let year = if matches!(day_end.trailing_punct(), "" | ",") && !day_end.sep.contains('\n') {
+            parse_year(words, i + len, true).or_else(|| {
+                parse_number(words, i + len)
+                    .filter(|y| (1000..=2100).contains(&y.int) && y.frac.is_none() && !y.ordinal)
+                    .map(|y| (y.int, y.len))
+            })
+        } else {
+            None
+        };
+        // "you may first ask" isn't a date either: a lowercase "may"
+        // or "march" needs "on" or "the" before it, or a year after it
+        let after_on_or_the = i > 0
+            && continues(&words[i - 1])
+            && matches!(key_at(words, i - 1).as_str(), "on" | "the");
+        if VERB_MONTHS.contains(&MONTHS[m]) && !words[i].starts_upper() && !after_on_or_the && year.is_none() {
+            return None;
+        }
+        if let Some((year, n)) = year {
+            text = format!("{}, {}", text, year);
+            len += n;
+        }
+        return Some((text, len));
+    }
+
+    let day = parse_number(words, i).filter(|d| d.ordinal && (1..=31).contains(&d.int))?;
+    let of = i + day.len;
+    if key_at(words, of) != "of" || !continues(&words[of - 1]) || !continues(&words[of]) {
+        return None;
+    }
+    let m = month(&key_at(words, of + 1))?;
+    Some((format!("{} of {}", day.format(), capitalized(MONTHS[m])), day.len + 2))
+}
+
+/// A number with whatever percent sign, currency or unit follows it.
+fn parse_quantity(words: &[Word], i: usize) -> Option<(String, usize)> {
+    let num = parse_year(words, i, false)
+        .map(|(int, len)| Number { int, frac: None, ordinal: false, digits: false, len })
+        .or_else(|| parse_number(words, i))?;
+    let end = i + num.len;
+    let open = continues(&words[end - 1]) && !num.ordinal;
+
+    if open {
+        let key = key_at(words, end);
+        if key == "percent" {
+            return Some((format!("{}%", num.format()), num.len + 1));
+        }
+        if key == "per" && key_at(words, end + 1) == "cent" && continues(&words[end]) {
+            return Some((format!("{}%", num.format()), num.len + 2));
+        }
+
+        if let Some((_, symbol)) = CURRENCIES.iter().find(|(name, _)| *name == key) {
+            let mut len = num.len + 1;
+            let mut amount = num.format();
+            // "five dollars and fifty cents"
+            if num.frac.is_none() && continues(&words[end]) && key_at(words, end + 1) == "and" {
+                if let Some(cents) = parse_number(words, end + 2).filter(|c| c.int < 100 && c.frac.is_none()) {
+                    let after = end + 2 + cents.len;
+                    if matches!(key_at(words, after).as_str(), "cents" | "cent")
+                        && continues(&words[end + 1])
+                        && continues(&words[after - 1])
+                    {
+                        amount = format!("{}.{:02}", amount, cents.int);
+                        len = after + 1 - i;
+                    }
+                }
+            }
+            return Some((format!("{}{}", symbol, amount), len));
+        }
+
+        for (phrase, symbol) in UNITS {
+            let matches = phrase.iter().enumerate().all(|(k, p)| {
+                key_at(words, end + k) == *p && (k + 1 == phrase.len() || continues(&words[end + k]))
+            });
+            if matches {
+                let sep = if symbol.starts_with('°') { "" } else { " " };
+                return Some((format!("{}{}{}", num.format(), sep, symbol), num.len + phrase.len()));
+            }
+        }
+    }
+
+    if num.digits || num.is_small_word() {
+        return None;
+    }
+    Some((num.format(), num.len))
+}
+
+/// Split hyphenated number words ("twenty-five") into separate words joined
+/// by a "-" separator, so they parse like spoken ones and re-join unchanged.
+fn split_hyphenated(words: Vec<Word>) -> Vec<Word> {
+    let mut out = Vec::with_capacity(words.len());
+    for word in words {
+        let core = word.core();
+        let parts: Vec<&str> = core.split('-').collect();
+        if parts.len() < 2 || !parts.iter().all(|p| is_number_word(&p.to_lowercase())) {
+            out.push(word);
+            continue;
+        }
+        let lead = word.leading_punct();
+        let trail = word.trailing_punct();
+        let last = parts.len() - 1;
+        for (k, part) in parts.iter().enumerate() {
+            let text = match k {
+                0 => format!("{}{}", lead, part),
+                k if k == last => format!("{}{}", part, trail),
+                _ => part.to_string(),
+            };
+            let sep = if k == last { word.sep.clone() } else { "-".to_string() };
+            out.push(Word::new(text, sep));
+        }
+    }
+    out
+}
+
+pub fn normalize(text: &str) -> String {
+    let (leading, words) = tokenize(text);
+    let words = split_hyphenated(words);
+
+    let mut out: Vec<Word> = Vec::with_capacity(words.len());
+    let mut i = 0;
+    while i < words.len() {
+        let parsed = parse_date(&words, i)
+            .or_else(|| parse_time(&words, i))
+            .or_else(|| parse_quantity(&words, i));
+        match parsed {
+            Some((written, len)) => {
+                let first = &words[i];
+                let last = &words[i + len - 1];
+                let text = format!("{}{}{}", first.leading_punct(), written, last.trailing_punct());
+                out.push(Word::new(text, last.sep.clone()));
+                i += len;
+            }
+            None => {
+                out.push(words[i].clone());
+                i += 1;
+            }
+        }
+    }
+    join(leading, out)
+}
@@ -0,0 +1,76 @@
+//! Inverse text normalization: spoken numbers, dates, times, currencies,
+//! units and percentages in their written form ("twenty five percent" ->
+//! "25%"). Each language has its own normalizer; languages without one, and
+//! text whose language is unknown, pass through unchanged.
+
+mod en;
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const SETTINGS_KEY: &str = "inverseTextNormalization";
+
+pub fn enabled(app: &AppHandle) -> bool {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> anyhow::Result<()> {
+    let store = app.store("settings.json")?;
+    store.set(SETTINGS_KEY, serde_json::json!(enabled));
+    Ok(())
+}
+
+/// Normalizer for `language`. None when the language is unknown, since
+/// English rules would mangle numbers and dates in other languages.
+fn normalizer(language: Option<&str>) -> Option<fn(&str) -> String> {
+    match language? {
+        "en" => Some(en::normalize),
+        _ => None,
+    }
+}
+
+/// Languages with a normalizer.
+pub fn supported_languages() -> &'static [&'static str] {
+    &["en"]
+}
+
+pub fn apply(enabled: bool, language: Option<&str>, text: &str) -> String {
+    match normalizer(language) {
+        Some(normalize) if enabled => normalize(text),
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en(text: &str) -> String {
+        apply(true, Some("en"), text)
+    }
+
+    #[test]
+    fn modal_may_is_not_a_date() {
+        assert_eq!(en("you may first want to check"), "you may first want to check");
+        assert_eq!(en("we may second that motion"), "we may second that motion");
+    }
+
+    #[test]
+    fn may_dates_with_context() {
+        assert_eq!(en("see you on may first"), "see you on May 1");
+        assert_eq!(en("it was May third"), "it was May 3");
+        assert_eq!(en("may fourth twenty twenty four"), "May 4, 2024");
+        assert_eq!(en("the fifth of may"), "the 5th of May");
+    }
+
+    #[test]
+    fn unknown_language_is_left_alone() {
+        assert_eq!(apply(true, None, "twenty five percent"), "twenty five percent");
+        assert_eq!(apply(true, Some("de"), "twenty five percent"), "twenty five percent");
+        assert_eq!(en("twenty five percent"), "25%");
+    }
+}
@@ -0,0 +1,271 @@
+//! Markdown dictation: "heading two", "bullet", "numbered item", "checkbox"
+//! and "bold ... end bold" become Markdown syntax. Lists carry over between
+//! consecutive dictations into the same app, so a second "numbered item"
+//! continues at 4 after a dictation that ended on item 3.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use super::words::{capitalize, join, tokenize, Word};
+
+/// How long a list stays open for the next dictation into the same app.
+const LIST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// The list the last dictation into an app ended in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListState {
+    Bullet,
+    Checkbox,
+    /// Number of the last item.
+    Numbered(u32),
+}
+
+static LISTS: LazyLock<Mutex<HashMap<String, (ListState, Instant)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Open list in `app`, if its last dictation ended in one recently.
+pub fn list_state(app: Option<&str>) -> Option<ListState> {
+    let app = app?;
+    let mut lists = LISTS.lock();
+    match lists.get(app) {
+        Some((state, at)) if at.elapsed() < LIST_TIMEOUT => Some(*state),
+        Some(_) => {
+            lists.remove(app);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Remember whether the text just pasted into `app` ended in a list item.
+pub fn remember(app: Option<&str>, text: &str) {
+    let Some(app) = app else {
+        return;
+    };
+    let last_line = text.lines().last().unwrap_or_default().trim_start();
+    let state = if last_line.starts_with("- [ ] ") || last_line.starts_with("- [x] ") {
+        Some(ListState::Checkbox)
+    } else if last_line.starts_with("- ") {
+        Some(ListState::Bullet)
+    } else {
+        last_line
+            .split_once(". ")
+            .and_then(|(n, _)| n.parse().ok())
+            .map(ListState::Numbered)
+    };
+    let mut lists = LISTS.lock();
+    match state {
+        Some(state) => {
+            lists.insert(app.to_string(), (state, Instant::now()));
+        }
+        None => {
+            lists.remove(app);
+        }
+    }
+}
+
+enum Block {
+    Heading(usize),
+    Bullet,
+    Numbered,
+    Checkbox,
+}
+
+const LEVELS: [&str; 6] = ["one", "two", "three", "four", "five", "six"];
+
+/// Block command at `words[i]` and how many words it spans.
+fn match_block(words: &[Word], i: usize) -> Option<(Block, usize)> {
+    let key = |k: usize| words.get(i + k).map(Word::key).unwrap_or_default();
+    match key(0).as_str() {
+        "heading" | "header" => {
+            let level = key(1);
+            let level = LEVELS
+                .iter()
+                .position(|l| *l == level)
+                .map(|p| p + 1)
+                .or_else(|| level.parse().ok().filter(|l| (1..=6).contains(l)))?;
+            Some((Block::Heading(level), 2))
+        }
+        "bullet" if key(1) == "point" => Some((Block::Bullet, 2)),
+        // "a bullet" or "the bullet" is a noun, not a command
+        "bullet" if i == 0 || !matches!(words[i - 1].key().as_str(), "a" | "the" | "one") => {
+            Some((Block::Bullet, 1))
+        }
+        "numbered" | "number" if key(1) == "item" => Some((Block::Numbered, 2)),
+        "checkbox" => Some((Block::Checkbox, 1)),
+        "check" if key(1) == "box" => Some((Block::Checkbox, 2)),
+        _ => None,
+    }
+}
+
+/// Inline spans: spoken opener, spoken closer, Markdown marker.
+const SPANS: &[(&str, &str, &str)] = &[("bold", "end bold", "**"), ("italic", "end italic", "*")];
+
+fn matches_at(words: &[Word], i: usize, phrase: &str) -> bool {
+    let parts: Vec<&str> = phrase.split(' ').collect();
+    parts.len() <= words.len() - i && parts.iter().enumerate().all(|(k, p)| words[i + k].key() == *p)
+}
+
+/// Put `marker` just inside the word's punctuation: `**word**,`.
+fn wrap_start(word: &mut Word, marker: &str) {
+    let at = word.leading_punct().len();
+    word.text.insert_str(at, marker);
+}
+
+fn wrap_end(word: &mut Word, marker: &str) {
+    let at = word.text.len() - word.trailing_punct().len();
+    word.text.insert_str(at, marker);
+}
+
+/// End the current line before a new block: drop the punctuation the engine
+/// left before the command and any full stop closing a list item or heading.
+/// `strip` is false when the last word is a bare block marker.
+fn end_line(out: &mut [Word], in_block: bool, strip: bool) {
+    let Some(last) = out.last_mut() else {
+        return;
+    };
+    if strip {
+        let core_end = last.text.len() - last.trailing_punct().len();
+        let kept: String = last.text[core_end..]
+            .chars()
+            .filter(|c| !matches!(c, ',' | ';' | ':') && (!in_block || *c != '.'))
+            .collect();
+        last.text.truncate(core_end);
+        last.text.push_str(&kept);
+    }
+    if !last.sep.contains('\n') {
+        last.sep = "\n".to_string();
+    }
+}
+
+pub fn apply(text: &str, list: Option<ListState>) -> String {
+    let (leading, words) = tokenize(text);
+    let mut out: Vec<Word> = Vec::with_capacity(words.len());
+    let mut number = match list {
+        Some(ListState::Numbered(n)) => n,
+        _ => 0,
+    };
+    let mut in_block = false;
+    // Index of the last block marker, which `end_line` leaves alone
+    let mut marker_at = None;
+    let mut capitalize_next = false;
+    let mut open_marker: Option<&str> = None;
+    let mut open_spans: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    while i < words.len() {
+        if let Some((block, n)) = match_block(&words, i) {
+            // A list continued from the previous dictation starts on a new line
+            let continues_list = out.is_empty() && list.is_some() && !matches!(block, Block::Heading(_));
+            let strip = marker_at.is_none_or(|m| m + 1 != out.len());
+            end_line(&mut out, in_block, strip);
+            let prefix = match block {
+                Block::Heading(level) => "#".repeat(level),
+                Block::Bullet => "-".to_string(),
+                Block::Numbered => {
+                    number += 1;
+                    format!("{}.", number)
+                }
+                Block::Checkbox => "- [ ]".to_string(),
+            };
+            let prefix = if continues_list { format!("\n{}", prefix) } else { prefix };
+            marker_at = Some(out.len());
+            out.push(Word::new(prefix, " "));
+            in_block = true;
+            capitalize_next = true;
+            i += n;
+            continue;
+        }
+
+        let opens = SPANS.iter().find(|(open, close, _)| {
+            matches_at(&words, i, open) && (i + 1..words.len()).any(|j| matches_at(&words, j, close))
+        });
+        if let Some((_, _, marker)) = opens {
+            open_marker = Some(marker);
+            open_spans.push(marker);
+            i += 1;
+            continue;
+        }
+        if let Some((close, marker)) = SPANS.iter().find_map(|(_, close, marker)| {
+            (matches_at(&words, i, close) && open_spans.contains(marker)).then_some((*close, *marker))
+        }) {
+            if let Some(last) = out.last_mut() {
+                let core_end = last.text.len() - last.trailing_punct().len();
+                last.text.truncate(core_end);
+                wrap_end(last, marker);
+                // Punctuation the engine put after "end bold" belongs after the span
+                let closing = &words[i + close.split(' ').count() - 1];
+                last.text.push_str(closing.trailing_punct());
+                last.sep = closing.sep.clone();
+            }
+            open_spans.retain(|m| *m != marker);
+            i += close.split(' ').count();
+            continue;
+        }
+
+        let mut word = words[i].clone();
+        if capitalize_next {
+            capitalize(&mut word);
+            capitalize_next = false;
+        }
+        if let Some(marker) = open_marker.take() {
+            wrap_start(&mut word, marker);
+        }
+        if word.sep.contains('\n') {
+            in_block = false;
+        }
+        out.push(word);
+        i += 1;
+    }
+
+    if in_block && marker_at.is_none_or(|m| m + 1 != out.len()) {
+        if let Some(last) = out.last_mut() {
+            if last.text.ends_with('.') && !last.text.ends_with("..") {
+                last.text.pop();
+            }
+        }
+    }
+    join(leading, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbered_items_count_up() {
+        assert_eq!(
+            apply("Shopping list. Numbered item milk. Numbered item eggs, numbered item bread.", None),
+            "Shopping list.\n1. Milk\n2. Eggs\n3. Bread"
+        );
+    }
+
+    #[test]
+    fn lists_continue_across_dictations() {
+        let app = Some("markdown-test-editor");
+        remember(app, "Shopping list\n1. Milk\n2. Eggs\n3. Bread");
+        let list = list_state(app);
+        assert_eq!(list, Some(ListState::Numbered(3)));
+        assert_eq!(apply("numbered item butter", list), "\n4. Butter");
+
+        remember(app, "Done.");
+        assert_eq!(list_state(app), None);
+        remember(app, "- [ ] Call Ann");
+        assert_eq!(list_state(app), Some(ListState::Checkbox));
+    }
+
+    #[test]
+    fn blocks_and_spans() {
+        for (input, expected) in [
+            ("heading two Plans for today.", "## Plans for today"),
+            ("bullet apples bullet pears", "- Apples\n- Pears"),
+            ("check box call the bank.", "- [ ] Call the bank"),
+            ("This is bold really end bold important.", "This is **really** important."),
+            ("I bought a bullet journal.", "I bought a bullet journal."),
+        ] {
+            assert_eq!(apply(input, None), expected, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Post-transcription text pipeline. Stages run in a fixed order on the raw
+//! transcript before it's pasted; each stage is driven by its own settings.
+
+pub mod code;
+pub mod fillers;
+pub mod itn;
+pub mod markdown;
+pub mod punctuation;
+pub mod rules;
+pub mod snippets;
+pub mod words;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// What kind of text is being dictated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Sentences: spoken punctuation, capitalization, written-out numbers.
+    #[default]
+    Prose,
+    /// Identifiers and symbols, with no capitalization or sentence punctuation.
+    Code,
+    /// Prose with spoken headings, lists and emphasis turned into Markdown.
+    Markdown,
+}
+
+/// What the pipeline knows about the dictation a transcript came from.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// Spoken language (ISO 639-1), configured or detected; `None` if unknown.
+    pub language: Option<String>,
+    pub mode: Mode,
+    /// Markdown list the previous dictation into the same app ended in.
+    pub list: Option<markdown::ListState>,
+    /// Frontmost app the text is pasted into, for snippet placeholders.
+    pub app_name: Option<String>,
+    /// Date, time and clipboard for snippet placeholders.
+    pub placeholders: snippets::Placeholders,
+    /// Stage settings changed for this dictation, e.g. by an app profile.
+    pub overrides: Overrides,
+}
+
+/// Per-dictation changes to the stored stage settings. `None` keeps the
+/// stored value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Overrides {
+    pub spoken_commands: Option<bool>,
+    pub filler_cleanup: Option<bool>,
+    pub inverse_text_normalization: Option<bool>,
+    /// Ids of the replacement rules to apply instead of every enabled one.
+    pub rules: Option<Vec<String>>,
+}
+
+/// Settings for every stage, read once per dictation and reused by its
+/// second pass.
+pub struct Pipeline {
+    fillers: fillers::FillerSettings,
+    spoken: punctuation::SpokenCommandSettings,
+    itn: bool,
+    rules: Vec<rules::Rule>,
+    snippets: snippets::Snippets,
+}
+
+impl Pipeline {
+    pub fn load(app: &AppHandle) -> Self {
+        Self {
+            fillers: fillers::load_settings(app),
+            spoken: punctuation::load_settings(app),
+            itn: itn::enabled(app),
+            rules: rules::load_rules(app),
+            snippets: snippets::Snippets::new(snippets::load_snippets(app)),
+        }
+    }
+
+    /// Apply `overrides` on top of the stored settings.
+    pub fn with_overrides(mut self, overrides: &Overrides) -> Self {
+        if let Some(enabled) = overrides.spoken_commands {
+            self.spoken.enabled = enabled;
+        }
+        if let Some(enabled) = overrides.filler_cleanup {
+            self.fillers.enabled = enabled;
+        }
+        if let Some(enabled) = overrides.inverse_text_normalization {
+            self.itn = enabled;
+        }
+        if let Some(ids) = &overrides.rules {
+            self.rules.retain(|r| ids.contains(&r.id));
+            for rule in &mut self.rules {
+                rule.enabled = true;
+            }
+        }
+        self
+    }
+
+    pub fn run(&self, text: &str, ctx: &Context) -> String {
+        self.expand_snippets(&self.run_marked(text, ctx), ctx)
+    }
+
+    /// Every stage except snippet expansion: snippet triggers are left as
+    /// marker characters, so a later step (the LLM polish) can't rewrite
+    /// the snippet text either. Finish with [`Pipeline::expand_snippets`].
+    pub fn run_marked(&self, text: &str, ctx: &Context) -> String {
+        let language = ctx.language.as_deref();
+        // Snippets expand last so no other stage rewrites their text
+        let text = self.snippets.mark(text);
+        let text = fillers::apply(&self.fillers, language, &text);
+        let text = match ctx.mode {
+            Mode::Prose => {
+                let text = punctuation::apply(&self.spoken, language, &text);
+                itn::apply(self.itn, language, &text)
+            }
+            // Spoken commands would capitalize and punctuate; code has its own symbols
+            Mode::Code => code::apply(&itn::apply(self.itn, language, &text)),
+            Mode::Markdown => {
+                let text = punctuation::apply(&self.spoken, language, &text);
+                let text = markdown::apply(&text, ctx.list);
+                itn::apply(self.itn, language, &text)
+            }
+        };
+        let text = rules::apply(&self.rules, &text);
+        trim(&text, ctx.mode)
+    }
+
+    /// Replace the snippet markers left by [`Pipeline::run_marked`].
+    pub fn expand_snippets(&self, text: &str, ctx: &Context) -> String {
+        let text = self.snippets.expand(text, ctx.app_name.as_deref(), &ctx.placeholders);
+        trim(&text, ctx.mode)
+    }
+}
+
+fn trim(text: &str, mode: Mode) -> String {
+    match mode {
+        // Keep the line break that continues a list from the last dictation
+        Mode::Markdown => text.trim_end().trim_start_matches([' ', '\t']).to_string(),
+        _ => text.trim().to_string(),
+    }
+}
+
+/// Run the configured pipeline over a raw transcript.
+pub fn process(app: &AppHandle, text: &str, ctx: &Context) -> String {
+    Pipeline::load(app).with_overrides(&ctx.overrides).run(text, ctx)
+}
@@ -0,0 +1,295 @@
+//! Spoken punctuation and formatting commands: "comma", "new line",
+//! "open quote" and so on become the characters they name. Built-in command
+//! sets follow the transcription language; users can add their own phrases.
+
+use regex::{Captures, NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const SETTINGS_KEY: &str = "spokenCommands";
+
+/// Marks a spot where the next letter starts a sentence. Private-use code
+/// point, removed before the text leaves this module.
+const CAPITALIZE: char = '\u{E000}';
+
+/// How an inserted symbol joins the words around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Attach {
+    /// Sticks to the previous word, replacing punctuation the engine put
+    /// there itself: `,` `.` `?`.
+    Punct,
+    /// Like [`Attach::Punct`] and starts a new sentence: `.` `?` `!`.
+    EndSentence,
+    /// Sticks to the previous word: closing quote or bracket.
+    Close,
+    /// Sticks to the next word: opening quote or bracket.
+    Open,
+    /// Stands alone and starts a new sentence: line and paragraph breaks.
+    Break,
+    /// Joins both neighbours without spaces: hyphen, slash.
+    Join,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpokenCommand {
+    pub phrase: String,
+    pub text: String,
+    pub attach: Attach,
+    /// ISO 639-1 code the command applies to; `None` applies to every language.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpokenCommandSettings {
+    pub enabled: bool,
+    /// User phrases, checked before the built-in ones.
+    pub custom: Vec<SpokenCommand>,
+}
+
+pub fn load_settings(app: &AppHandle) -> SpokenCommandSettings {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &AppHandle, settings: &SpokenCommandSettings) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        settings.custom.iter().all(|c| !c.phrase.trim().is_empty()),
+        "Spoken commands need a phrase"
+    );
+    let store = app.store("settings.json")?;
+    store.set(SETTINGS_KEY, serde_json::to_value(settings)?);
+    Ok(())
+}
+
+type Builtin = (&'static str, &'static str, Attach);
+
+const EN: &[Builtin] = &[
+    ("new paragraph", "\n\n", Attach::Break),
+    ("new line", "\n", Attach::Break),
+    ("newline", "\n", Attach::Break),
+    ("comma", ",", Attach::Punct),
+    ("period", ".", Attach::EndSentence),
+    ("full stop", ".", Attach::EndSentence),
+    ("question mark", "?", Attach::EndSentence),
+    ("exclamation mark", "!", Attach::EndSentence),
+    ("exclamation point", "!", Attach::EndSentence),
+    ("colon", ":", Attach::Punct),
+    ("semicolon", ";", Attach::Punct),
+    ("ellipsis", "...", Attach::Punct),
+    ("open quote", "\"", Attach::Open),
+    ("close quote", "\"", Attach::Close),
+    ("end quote", "\"", Attach::Close),
+    ("open paren", "(", Attach::Open),
+    ("open parenthesis", "(", Attach::Open),
+    ("close paren", ")", Attach::Close),
+    ("close parenthesis", ")", Attach::Close),
+    ("hyphen", "-", Attach::Join),
+    ("slash", "/", Attach::Join),
+];
+
+const DE: &[Builtin] = &[
+    ("neuer absatz", "\n\n", Attach::Break),
+    ("neue zeile", "\n", Attach::Break),
+    ("komma", ",", Attach::Punct),
+    ("punkt", ".", Attach::EndSentence),
+    ("fragezeichen", "?", Attach::EndSentence),
+    ("ausrufezeichen", "!", Attach::EndSentence),
+    ("doppelpunkt", ":", Attach::Punct),
+    ("semikolon", ";", Attach::Punct),
+    ("anführungszeichen auf", "„", Attach::Open),
+    ("anführungszeichen zu", "“", Attach::Close),
+    ("klammer auf", "(", Attach::Open),
+    ("klammer zu", ")", Attach::Close),
+    ("bindestrich", "-", Attach::Join),
+];
+
+// "point" and "punto" alone are everyday words, so only the unambiguous
+// forms are commands in French and Spanish.
+const FR: &[Builtin] = &[
+    ("nouveau paragraphe", "\n\n", Attach::Break),
+    ("nouvelle ligne", "\n", Attach::Break),
+    ("à la ligne", "\n", Attach::Break),
+    ("virgule", ",", Attach::Punct),
+    ("point final", ".", Attach::EndSentence),
+    ("point d'interrogation", "?", Attach::EndSentence),
+    ("point d'exclamation", "!", Attach::EndSentence),
+    ("point-virgule", ";", Attach::Punct),
+    ("point virgule", ";", Attach::Punct),
+    ("deux points", ":", Attach::Punct),
+    ("ouvrez les guillemets", "«\u{a0}", Attach::Open),
+    ("ouvrir les guillemets", "«\u{a0}", Attach::Open),
+    ("fermez les guillemets", "\u{a0}»", Attach::Close),
+    ("fermer les guillemets", "\u{a0}»", Attach::Close),
+    ("ouvrez la parenthèse", "(", Attach::Open),
+    ("ouvrir la parenthèse", "(", Attach::Open),
+    ("fermez la parenthèse", ")", Attach::Close),
+    ("fermer la parenthèse", ")", Attach::Close),
+    ("trait d'union", "-", Attach::Join),
+];
+
+const ES: &[Builtin] = &[
+    ("nuevo párrafo", "\n\n", Attach::Break),
+    ("punto y aparte", ".\n", Attach::Break),
+    ("nueva línea", "\n", Attach::Break),
+    ("punto y coma", ";", Attach::Punct),
+    ("punto y seguido", ".", Attach::EndSentence),
+    ("punto final", ".", Attach::EndSentence),
+    ("coma", ",", Attach::Punct),
+    ("dos puntos", ":", Attach::Punct),
+    ("signo de interrogación", "?", Attach::EndSentence),
+    ("signo de exclamación", "!", Attach::EndSentence),
+    ("abrir comillas", "\"", Attach::Open),
+    ("cerrar comillas", "\"", Attach::Close),
+    ("abrir paréntesis", "(", Attach::Open),
+    ("cerrar paréntesis", ")", Attach::Close),
+    ("guion", "-", Attach::Join),
+];
+
+/// Built-in commands for `language`, English when the language is unknown.
+pub fn builtin_commands(language: Option<&str>) -> Vec<SpokenCommand> {
+    let (code, set) = match language.unwrap_or("en") {
+        "de" => ("de", DE),
+        "fr" => ("fr", FR),
+        "es" => ("es", ES),
+        "en" => ("en", EN),
+        _ => return Vec::new(),
+    };
+    set.iter()
+        .map(|(phrase, text, attach)| SpokenCommand {
+            phrase: phrase.to_string(),
+            text: text.to_string(),
+            attach: *attach,
+            language: Some(code.to_string()),
+        })
+        .collect()
+}
+
+fn command_regex(command: &SpokenCommand) -> Option<Regex> {
+    // Words may be separated by any whitespace and apostrophes may be curly
+    let words: Vec<String> = command
+        .phrase
+        .split_whitespace()
+        .map(|w| regex::escape(w).replace('\'', "['’]"))
+        .collect();
+    let phrase = words.join(r"\s+");
+    // Engines often punctuate around a spoken command ("Hello, comma, world")
+    let (lead, trail) = match command.attach {
+        Attach::Punct | Attach::EndSentence => (r"[\s,.;:!?]*", r"[,.;:!?]*[ \t]*"),
+        Attach::Close => (r"\s*", r"[ \t]*"),
+        Attach::Open => (r"[ \t]*", r"[,.;:!?]*\s*"),
+        Attach::Break => (r"[ \t]*", r"[,.;:!?]*[ \t]*"),
+        Attach::Join => (r"\s*", r"\s*"),
+    };
+    RegexBuilder::new(&format!(r"{}\b{}\b{}", lead, phrase, trail))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+fn replacement(command: &SpokenCommand) -> String {
+    match command.attach {
+        Attach::Punct | Attach::Close => format!("{} ", command.text),
+        Attach::EndSentence => format!("{} {}", command.text, CAPITALIZE),
+        Attach::Open => format!(" {}", command.text),
+        Attach::Break => format!("{}{}", command.text, CAPITALIZE),
+        Attach::Join => command.text.clone(),
+    }
+}
+
+static SPACE_BEFORE_PUNCT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[ \t]+([,.;:!?)])").unwrap());
+static SPACE_AROUND_NEWLINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[ \t]*\n[ \t]*").unwrap());
+static REPEATED_SPACE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[ \t]{2,}").unwrap());
+static CAPITALIZE_NEXT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"{}([^\p{{L}}]*)(\p{{L}})?", CAPITALIZE)).unwrap());
+
+/// Replace spoken commands in `text`. Custom commands win over built-ins,
+/// and longer phrases over shorter ones ("punto y coma" before "coma").
+pub fn apply(settings: &SpokenCommandSettings, language: Option<&str>, text: &str) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let lang = language.unwrap_or("en");
+    let mut commands: Vec<SpokenCommand> = settings
+        .custom
+        .iter()
+        .filter(|c| c.language.as_deref().is_none_or(|l| l == lang))
+        .cloned()
+        .collect();
+    let mut builtin = builtin_commands(language);
+    builtin.sort_by_key(|c| std::cmp::Reverse(c.phrase.len()));
+    commands.extend(builtin);
+
+    let mut text = text.to_string();
+    for command in &commands {
+        let Some(re) = command_regex(command) else {
+            continue;
+        };
+        let insert = replacement(command);
+        text = re.replace_all(&text, NoExpand(&insert)).into_owned();
+    }
+
+    let text = CAPITALIZE_NEXT.replace_all(&text, |caps: &Captures| {
+        let gap = caps.get(1).map_or("", |m| m.as_str());
+        let letter = caps.get(2).map_or(String::new(), |m| m.as_str().to_uppercase());
+        format!("{}{}", gap, letter)
+    });
+    let text = text.replace(CAPITALIZE, "");
+    let text = SPACE_AROUND_NEWLINE.replace_all(&text, "\n");
+    let text = SPACE_BEFORE_PUNCT.replace_all(&text, "$1");
+    let text = REPEATED_SPACE.replace_all(&text, " ");
+    text.trim_matches(|c: char| c == ' ' || c == '\t').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> SpokenCommandSettings {
+        SpokenCommandSettings { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn english_commands() {
+        let settings = enabled();
+        for (input, expected) in [
+            ("hello comma world period how are you question mark", "hello, world. How are you?"),
+            ("Hello, comma, world. Period.", "Hello, world."),
+            ("first line new line second line", "first line\nSecond line"),
+            ("he said open quote hi close quote", "he said \"hi\""),
+            ("a well hyphen known fact", "a well-known fact"),
+        ] {
+            assert_eq!(apply(&settings, Some("en"), input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn longer_phrases_win() {
+        assert_eq!(apply(&enabled(), Some("es"), "uno punto y coma dos"), "uno; dos");
+        assert_eq!(apply(&enabled(), Some("de"), "Hallo Komma Welt"), "Hallo, Welt");
+    }
+
+    #[test]
+    fn custom_commands_and_disabled() {
+        let settings = SpokenCommandSettings {
+            enabled: true,
+            custom: vec![SpokenCommand {
+                phrase: "smiley".to_string(),
+                text: ":)".to_string(),
+                attach: Attach::Close,
+                language: None,
+            }],
+        };
+        assert_eq!(apply(&settings, Some("en"), "thanks smiley"), "thanks:)");
+        let disabled = SpokenCommandSettings::default();
+        assert_eq!(apply(&disabled, Some("en"), "hello comma world"), "hello comma world");
+    }
+}
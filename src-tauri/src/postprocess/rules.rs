@@ -0,0 +1,183 @@
+//! User-defined literal and regex replacement rules, applied in list order.
+
+use anyhow::{Context, Result};
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::settings_list::{assign_ids, default_true};
+
+const RULES_KEY: &str = "replacementRules";
+const EXPORT_FORMAT: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    /// Match the pattern text as-is; the replacement is inserted verbatim.
+    Literal,
+    /// Match a regular expression; the replacement may use `$1` / `${name}`.
+    Regex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub kind: RuleKind,
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Literal rules only: match whole words, so "cat" leaves "catalog" alone.
+    #[serde(default)]
+    pub whole_word: bool,
+}
+
+impl Rule {
+    fn compile(&self) -> Result<Regex> {
+        let pattern = match self.kind {
+            RuleKind::Literal if self.whole_word => format!(r"\b{}\b", regex::escape(&self.pattern)),
+            RuleKind::Literal => regex::escape(&self.pattern),
+            RuleKind::Regex => self.pattern.clone(),
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .with_context(|| format!("Invalid pattern in rule {:?}", self.label()))
+    }
+
+    fn label(&self) -> &str {
+        if self.name.is_empty() { &self.pattern } else { &self.name }
+    }
+}
+
+/// File written by [`export_rules`].
+#[derive(Serialize, Deserialize)]
+struct RulesFile {
+    format: u32,
+    rules: Vec<Rule>,
+}
+
+pub fn load_rules(app: &AppHandle) -> Vec<Rule> {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(RULES_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and store `rules`, giving any rule without an id a fresh one.
+pub fn save_rules(app: &AppHandle, mut rules: Vec<Rule>) -> Result<Vec<Rule>> {
+    anyhow::ensure!(
+        rules.iter().all(|r| !r.pattern.is_empty()),
+        "Replacement rules need a pattern"
+    );
+    for rule in &rules {
+        rule.compile()?;
+    }
+    assign_ids(&mut rules, "rule", |r| &mut r.id);
+    let store = app.store("settings.json")?;
+    store.set(RULES_KEY, serde_json::to_value(&rules)?);
+    Ok(rules)
+}
+
+pub fn export_rules(app: &AppHandle, dest: &Path) -> Result<usize> {
+    let rules = load_rules(app);
+    let file = RulesFile { format: EXPORT_FORMAT, rules };
+    let json = serde_json::to_string_pretty(&file)?;
+    std::fs::write(dest, json).with_context(|| format!("Failed to write {:?}", dest))?;
+    Ok(file.rules.len())
+}
+
+/// Load rules from an exported file, replacing the current list or appending
+/// to it. Returns the stored rules.
+pub fn import_rules(app: &AppHandle, src: &Path, replace: bool) -> Result<Vec<Rule>> {
+    let data = std::fs::read_to_string(src).with_context(|| format!("Failed to read {:?}", src))?;
+    let file: RulesFile = serde_json::from_str(&data).context("Not a replacement rules file")?;
+    anyhow::ensure!(
+        file.format <= EXPORT_FORMAT,
+        "Rules file format {} is newer than this app supports",
+        file.format
+    );
+    let rules = if replace {
+        file.rules
+    } else {
+        let mut rules = load_rules(app);
+        rules.extend(file.rules);
+        rules
+    };
+    save_rules(app, rules)
+}
+
+/// Apply every enabled rule in order. Rules that fail to compile are skipped
+/// (they can't be saved, but the store may be edited by hand).
+pub fn apply(rules: &[Rule], text: &str) -> String {
+    let mut text = text.to_string();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let re = match rule.compile() {
+            Ok(re) => re,
+            Err(e) => {
+                eprintln!("[audioshift] Skipping replacement rule: {:#}", e);
+                continue;
+            }
+        };
+        text = match rule.kind {
+            RuleKind::Literal => re.replace_all(&text, NoExpand(&rule.replacement)).into_owned(),
+            RuleKind::Regex => re.replace_all(&text, rule.replacement.as_str()).into_owned(),
+        };
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: RuleKind, pattern: &str, replacement: &str) -> Rule {
+        Rule {
+            id: String::new(),
+            name: String::new(),
+            kind,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            enabled: true,
+            case_sensitive: false,
+            whole_word: false,
+        }
+    }
+
+    #[test]
+    fn literal_rules() {
+        let plain = rule(RuleKind::Literal, "cat", "dog");
+        assert_eq!(apply(&[plain.clone()], "Cat in the catalog"), "dog in the dogalog");
+
+        let whole = Rule { whole_word: true, ..plain.clone() };
+        assert_eq!(apply(&[whole], "Cat in the catalog"), "dog in the catalog");
+
+        let sensitive = Rule { case_sensitive: true, ..plain };
+        assert_eq!(apply(&[sensitive], "Cat and cat"), "Cat and dog");
+
+        // Literal replacements are inserted verbatim.
+        let dollars = rule(RuleKind::Literal, "price", "$1.00");
+        assert_eq!(apply(&[dollars], "the price"), "the $1.00");
+    }
+
+    #[test]
+    fn regex_rules_apply_in_order() {
+        let rules = [
+            rule(RuleKind::Regex, r"(\w+)@(\w+)\.com", "$1 at $2"),
+            rule(RuleKind::Literal, "at", "@"),
+            Rule { enabled: false, ..rule(RuleKind::Literal, "mail", "post") },
+            rule(RuleKind::Regex, "(unclosed", "x"),
+        ];
+        assert_eq!(apply(&rules, "mail ann@example.com"), "mail ann @ example");
+    }
+}
@@ -0,0 +1,266 @@
+//! Voice snippets: "insert snippet signature" is replaced with the stored
+//! text of the snippet named "signature". Snippet text can hold placeholders
+//! filled in at expansion time:
+//!
+//! - `{date}` and `{time}`, or `{date:%d %B}` with a strftime format
+//! - `{clipboard}`: the clipboard's text
+//! - `{app}`: the name of the app being dictated into
+
+use anyhow::Result;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, LazyLock, OnceLock};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::paster;
+use crate::settings_list::{assign_ids, default_true};
+
+const SNIPPETS_KEY: &str = "snippets";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    #[serde(default)]
+    pub id: String,
+    /// Spoken after "insert snippet"; matched case-insensitively.
+    pub name: String,
+    pub text: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{(date|time|clipboard|app)(?::([^}]*))?\}").unwrap());
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M";
+
+/// First character of the private use range standing in for snippet `n`
+/// while the other stages run, so they leave the snippet text alone.
+const MARKER_BASE: u32 = 0xF0000;
+
+fn marker(n: usize) -> Option<char> {
+    char::from_u32(MARKER_BASE + n as u32).filter(|_| n < 0xFFFE)
+}
+
+/// Snippet markers in `text`, sorted, to check that a rewrite kept them all.
+pub fn markers(text: &str) -> Vec<char> {
+    let mut markers: Vec<char> = text.chars().filter(|c| (*c as u32) >= MARKER_BASE).collect();
+    markers.sort_unstable();
+    markers
+}
+
+pub fn load_snippets(app: &AppHandle) -> Vec<Snippet> {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SNIPPETS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and store `snippets`, giving any snippet without an id a fresh one.
+pub fn save_snippets(app: &AppHandle, mut snippets: Vec<Snippet>) -> Result<Vec<Snippet>> {
+    let mut names = std::collections::HashSet::new();
+    for snippet in &snippets {
+        let name = normalize_name(&snippet.name);
+        anyhow::ensure!(!name.is_empty(), "Snippets need a name made of words");
+        anyhow::ensure!(names.insert(name), "Duplicate snippet name: {:?}", snippet.name);
+        for caps in PLACEHOLDER.captures_iter(&snippet.text) {
+            if let Some(format) = caps.get(2) {
+                anyhow::ensure!(
+                    valid_format(format.as_str()),
+                    "Invalid date format in snippet {:?}: {}",
+                    snippet.name,
+                    format.as_str()
+                );
+            }
+        }
+    }
+
+    assign_ids(&mut snippets, "snippet", |s| &mut s.id);
+
+    let store = app.store("settings.json")?;
+    store.set(SNIPPETS_KEY, serde_json::to_value(&snippets)?);
+    Ok(snippets)
+}
+
+/// Lowercase words of a snippet name, space-separated.
+fn normalize_name(name: &str) -> String {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether chrono can format with `format` without panicking.
+fn valid_format(format: &str) -> bool {
+    !chrono::format::StrftimeItems::new(format).any(|item| matches!(item, chrono::format::Item::Error))
+}
+
+/// Trigger phrase for a snippet. Engines punctuate and capitalize freely, so
+/// words may be separated by any non-word characters.
+fn trigger(snippet: &Snippet) -> Option<Regex> {
+    let name = normalize_name(&snippet.name);
+    if name.is_empty() {
+        return None;
+    }
+    let words: Vec<String> = name.split(' ').map(regex::escape).collect();
+    Regex::new(&format!(
+        r"(?i)\binsert\s+snippet\W+{}\b[.,!?;:]*",
+        words.join(r"\W+")
+    ))
+    .ok()
+}
+
+/// Placeholder values for one dictation. Fixed when first used, so a second
+/// pass over the same dictation fills snippets in the same way even after the
+/// clock or the clipboard moved on. Clones share the values.
+#[derive(Debug, Clone)]
+pub struct Placeholders {
+    now: chrono::DateTime<chrono::Local>,
+    clipboard: Arc<OnceLock<String>>,
+}
+
+impl Default for Placeholders {
+    fn default() -> Self {
+        Self {
+            now: chrono::Local::now(),
+            clipboard: Arc::default(),
+        }
+    }
+}
+
+impl Placeholders {
+    /// The clipboard's text, read on first use.
+    fn clipboard(&self) -> &str {
+        self.clipboard.get_or_init(|| match paster::read_clipboard() {
+            Ok(text) => text.unwrap_or_default(),
+            Err(e) => {
+                eprintln!("[audioshift] Snippet clipboard placeholder: {}", e);
+                String::new()
+            }
+        })
+    }
+}
+
+/// Enabled snippets with their triggers, longest name first so "signature
+/// work" wins over "signature".
+pub struct Snippets(Vec<(Regex, Snippet)>);
+
+impl Snippets {
+    pub fn new(snippets: Vec<Snippet>) -> Self {
+        let mut triggers: Vec<(Regex, Snippet)> = snippets
+            .into_iter()
+            .filter(|s| s.enabled)
+            .filter_map(|s| trigger(&s).map(|re| (re, s)))
+            .collect();
+        triggers.sort_by_key(|(_, s)| std::cmp::Reverse(normalize_name(&s.name).len()));
+        Self(triggers)
+    }
+
+    /// Replace each trigger phrase in `text` with a marker character.
+    pub fn mark(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (n, (re, _)) in self.0.iter().enumerate() {
+            let Some(marker) = marker(n) else {
+                break;
+            };
+            if re.is_match(&text) {
+                text = re.replace_all(&text, marker.to_string()).into_owned();
+            }
+        }
+        text
+    }
+
+    /// Replace the markers left by [`Snippets::mark`] with the snippet text.
+    pub fn expand(&self, text: &str, app_name: Option<&str>, values: &Placeholders) -> String {
+        if !text.chars().any(|c| (c as u32) >= MARKER_BASE) {
+            return text.to_string();
+        }
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            let index = (c as u32).checked_sub(MARKER_BASE).map(|n| n as usize);
+            match index.and_then(|n| self.0.get(n)) {
+                Some((_, snippet)) => out.push_str(&fill(&snippet.text, app_name, values)),
+                None => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+/// Fill in placeholders. The clipboard is only read if a snippet uses it.
+fn fill(text: &str, app_name: Option<&str>, values: &Placeholders) -> String {
+    PLACEHOLDER
+        .replace_all(text, |caps: &Captures| {
+            let format = caps.get(2).map(|m| m.as_str());
+            match &caps[1] {
+                "date" | "time" => {
+                    let default = if &caps[1] == "date" { DEFAULT_DATE_FORMAT } else { DEFAULT_TIME_FORMAT };
+                    let format = format.filter(|f| !f.is_empty()).unwrap_or(default);
+                    if valid_format(format) {
+                        values.now.format(format).to_string()
+                    } else {
+                        caps[0].to_string()
+                    }
+                }
+                "clipboard" => values.clipboard().to_string(),
+                _ => app_name.unwrap_or_default().to_string(),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snippet(name: &str, text: &str) -> Snippet {
+        Snippet { id: String::new(), name: name.to_string(), text: text.to_string(), enabled: true }
+    }
+
+    fn placeholders() -> Placeholders {
+        Placeholders {
+            now: chrono::Local.with_ymd_and_hms(2026, 3, 14, 9, 5, 0).unwrap(),
+            clipboard: Arc::new(OnceLock::from("copied text".to_string())),
+        }
+    }
+
+    #[test]
+    fn markers_round_trip() {
+        let snippets = Snippets::new(vec![
+            snippet("signature", "Best, Ann"),
+            snippet("signature work", "Ann Lee, ACME"),
+            Snippet { enabled: false, ..snippet("address", "1 Main St") },
+        ]);
+        let marked = snippets.mark("Thanks. Insert snippet, signature work. Insert snippet signature! insert snippet address");
+        assert_eq!(markers(&marked).len(), 2);
+        assert!(!marked.to_lowercase().contains("signature"));
+        // Other stages only see the markers, so a rewrite can't touch the text
+        let rewritten = marked.replace("Thanks.", "Thank you.");
+        assert_eq!(
+            snippets.expand(&rewritten, None, &placeholders()),
+            "Thank you. Ann Lee, ACME Best, Ann insert snippet address"
+        );
+    }
+
+    #[test]
+    fn placeholders_are_filled() {
+        let snippets = Snippets::new(vec![snippet("stamp", "{date} {time} {date:%d %B} in {app}: {clipboard}")]);
+        let marked = snippets.mark("insert snippet stamp");
+        assert_eq!(
+            snippets.expand(&marked, Some("Notes"), &placeholders()),
+            "2026-03-14 09:05 14 March in Notes: copied text"
+        );
+    }
+
+    #[test]
+    fn text_without_markers_is_unchanged() {
+        let snippets = Snippets::new(vec![snippet("signature", "Best, Ann")]);
+        assert_eq!(snippets.mark("my signature"), "my signature");
+        assert_eq!(snippets.expand("my signature", None, &placeholders()), "my signature");
+    }
+}
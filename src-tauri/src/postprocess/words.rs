@@ -0,0 +1,83 @@
+//! Whitespace tokenization shared by the word-level stages. Splitting and
+//! re-joining a text through [`tokenize`] and [`join`] is lossless, so a
+//! stage only changes the words it means to.
+
+#[derive(Debug, Clone)]
+pub struct Word {
+    /// The word with any punctuation attached to it.
+    pub text: String,
+    /// Whitespace that followed the word in the input.
+    pub sep: String,
+}
+
+impl Word {
+    pub fn new(text: impl Into<String>, sep: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            sep: sep.into(),
+        }
+    }
+
+    /// The word without surrounding punctuation.
+    pub fn core(&self) -> &str {
+        self.text.trim_matches(|c: char| !c.is_alphanumeric())
+    }
+
+    /// Lowercased [`Word::core`], for matching.
+    pub fn key(&self) -> String {
+        self.core().to_lowercase()
+    }
+
+    pub fn leading_punct(&self) -> &str {
+        let start = self.text.find(|c: char| c.is_alphanumeric()).unwrap_or(self.text.len());
+        &self.text[..start]
+    }
+
+    pub fn trailing_punct(&self) -> &str {
+        let core_end = self.text.rfind(|c: char| c.is_alphanumeric()).map_or(0, |i| {
+            i + self.text[i..].chars().next().map_or(0, char::len_utf8)
+        });
+        &self.text[core_end..]
+    }
+
+    pub fn ends_sentence(&self) -> bool {
+        self.trailing_punct().contains(['.', '!', '?']) || self.sep.contains('\n')
+    }
+
+    pub fn starts_upper(&self) -> bool {
+        self.core().chars().next().is_some_and(char::is_uppercase)
+    }
+}
+
+/// Split `text` into words, returning the leading whitespace separately.
+pub fn tokenize(text: &str) -> (String, Vec<Word>) {
+    let leading: String = text.chars().take_while(|c| c.is_whitespace()).collect();
+    let mut words = Vec::new();
+    let mut rest = &text[leading.len()..];
+    while !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(end);
+        let sep_len = after.find(|c: char| !c.is_whitespace()).unwrap_or(after.len());
+        words.push(Word::new(word, &after[..sep_len]));
+        rest = &after[sep_len..];
+    }
+    (leading, words)
+}
+
+pub fn join(leading: String, words: Vec<Word>) -> String {
+    let mut out = leading;
+    for word in words {
+        out.push_str(&word.text);
+        out.push_str(&word.sep);
+    }
+    out
+}
+
+/// Uppercase the first letter of `word`.
+pub fn capitalize(word: &mut Word) {
+    if let Some(pos) = word.text.find(char::is_alphabetic) {
+        let c = word.text[pos..].chars().next().unwrap();
+        let upper: String = c.to_uppercase().collect();
+        word.text.replace_range(pos..pos + c.len_utf8(), &upper);
+    }
+}
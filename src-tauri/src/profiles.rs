@@ -0,0 +1,133 @@
+//! Per-application dictation profiles. A profile matches the frontmost app by
+//! name and/or window title and overrides the model, language, output and
+//! post-processing settings for dictations into that app. The first enabled
+//! profile that matches wins.
+
+use anyhow::{Context, Result};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::paster::{PasteChord, PasteMode};
+use crate::settings_list::{assign_ids, default_true};
+use crate::{model_registry, postprocess, typer};
+
+const PROFILES_KEY: &str = "profiles";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Frontmost app name, compared case-insensitively.
+    #[serde(default)]
+    pub app_name: Option<String>,
+    /// Case-insensitive regex searched in the window title.
+    #[serde(default)]
+    pub window_title: Option<String>,
+
+    #[serde(default)]
+    pub live_model: Option<String>,
+    /// ISO 639-1 code, or "auto" to detect even when a language is configured.
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub translate: Option<bool>,
+    #[serde(default)]
+    pub paste_mode: Option<PasteMode>,
+    /// Paste keystroke on Linux, e.g. Ctrl+Shift+V for terminals.
+    #[serde(default)]
+    pub paste_chord: Option<PasteChord>,
+    /// Initial prompt for Whisper models: names, jargon, preferred style.
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub mode: Option<postprocess::Mode>,
+    #[serde(default)]
+    pub post_processing: postprocess::Overrides,
+}
+
+impl Profile {
+    /// Whether the profile applies to the given frontmost app. A profile
+    /// without any criteria never matches; with both, both must match.
+    fn matches(&self, app_name: Option<&str>, window_title: Option<&str>) -> bool {
+        if !self.enabled || (self.app_name.is_none() && self.window_title.is_none()) {
+            return false;
+        }
+        let name_ok = self.app_name.as_deref().is_none_or(|want| {
+            app_name.is_some_and(|name| name.trim().eq_ignore_ascii_case(want.trim()))
+        });
+        let title_ok = self.window_title.as_deref().is_none_or(|pattern| {
+            let Some(title) = window_title else {
+                return false;
+            };
+            match title_regex(pattern) {
+                Ok(re) => re.is_match(title),
+                Err(e) => {
+                    eprintln!("[audioshift] Skipping profile {:?}: {}", self.name, e);
+                    false
+                }
+            }
+        });
+        name_ok && title_ok
+    }
+}
+
+fn title_regex(pattern: &str) -> Result<regex::Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .with_context(|| format!("Invalid window title pattern {:?}", pattern))
+}
+
+pub fn load_profiles(app: &AppHandle) -> Vec<Profile> {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(PROFILES_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Validate and store `profiles`, giving any profile without an id a fresh one.
+pub fn save_profiles(app: &AppHandle, mut profiles: Vec<Profile>) -> Result<Vec<Profile>> {
+    for profile in &profiles {
+        anyhow::ensure!(!profile.name.trim().is_empty(), "Profiles need a name");
+        anyhow::ensure!(
+            profile.app_name.is_some() || profile.window_title.is_some(),
+            "Profile {:?} needs an app name or window title to match",
+            profile.name
+        );
+        if let Some(pattern) = &profile.window_title {
+            title_regex(pattern)?;
+        }
+        anyhow::ensure!(
+            profile.paste_mode != Some(PasteMode::Type) || typer::supported(),
+            "Profile {:?} types its text, which is not supported on this platform yet",
+            profile.name
+        );
+        if let Some(model_id) = &profile.live_model {
+            anyhow::ensure!(
+                model_id == model_registry::AUTO_MODEL_ID || model_registry::find_model(model_id).is_some(),
+                "Unknown model: {}",
+                model_id
+            );
+        }
+    }
+
+    assign_ids(&mut profiles, "profile", |p| &mut p.id);
+
+    let store = app.store("settings.json")?;
+    store.set(PROFILES_KEY, serde_json::to_value(&profiles)?);
+    Ok(profiles)
+}
+
+/// The profile for the given frontmost app, if any.
+pub fn resolve(app: &AppHandle, app_name: Option<&str>, window_title: Option<&str>) -> Option<Profile> {
+    load_profiles(app)
+        .into_iter()
+        .find(|p| p.matches(app_name, window_title))
+}
@@ -0,0 +1,311 @@
+//! Redaction of personal data: email addresses, phone numbers, card and IBAN
+//! numbers, and user-defined patterns. Applied separately to what's written
+//! to history (`meta.json` lives in ~/Documents and gets backed up and
+//! synced) and to what's pasted.
+
+use anyhow::{Context, Result};
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::file_storage::RecordingMeta;
+use crate::refine::DiffSpan;
+use crate::settings_list::default_true;
+
+const SETTINGS_KEY: &str = "redaction";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomPattern {
+    pub name: String,
+    /// Case-insensitive regex.
+    pub pattern: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RedactionSettings {
+    /// Redact text, raw text and drafts before they're written to history.
+    pub redact_history: bool,
+    /// Redact the text that's pasted or copied.
+    pub redact_pasted: bool,
+    pub emails: bool,
+    pub phone_numbers: bool,
+    pub card_numbers: bool,
+    pub ibans: bool,
+    pub custom_patterns: Vec<CustomPattern>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            redact_history: false,
+            redact_pasted: false,
+            emails: true,
+            phone_numbers: true,
+            card_numbers: true,
+            ibans: true,
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b").unwrap());
+static IBAN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b[a-z]{2}\d{2}(?:[ -]?[a-z0-9]){11,30}\b").unwrap());
+static CARD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap());
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\+?\(?\d[\d().\- ]{5,}\d").unwrap());
+/// ISO dates and similar that look like phone numbers.
+static DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d{4}[-./]\d{1,2}[-./]\d{1,2}$").unwrap());
+
+const EMAIL_MASK: &str = "[email]";
+const PHONE_MASK: &str = "[phone]";
+const CARD_MASK: &str = "[card]";
+const IBAN_MASK: &str = "[iban]";
+const CUSTOM_MASK: &str = "[redacted]";
+
+fn digits(s: &str) -> Vec<u32> {
+    s.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Luhn checksum, which every payment card number passes.
+fn luhn_valid(number: &str) -> bool {
+    let digits = digits(number);
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match i % 2 {
+            0 => d,
+            _ if d * 2 > 9 => d * 2 - 9,
+            _ => d * 2,
+        })
+        .sum();
+    (13..=19).contains(&digits.len()) && sum.is_multiple_of(10)
+}
+
+/// ISO 13616 mod-97 check.
+fn iban_valid(iban: &str) -> bool {
+    let compact: String = iban.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_uppercase();
+    if !(15..=34).contains(&compact.len()) {
+        return false;
+    }
+    let (head, tail) = compact.split_at(4);
+    let mut remainder = 0u32;
+    for c in tail.chars().chain(head.chars()) {
+        let value = c.to_digit(36).unwrap_or(0);
+        remainder = if value > 9 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// Plain digit runs are amounts and ids ("1500000" from ITN); a phone number
+/// is written with a leading + or with separators between digit groups.
+fn phone_like(candidate: &str) -> bool {
+    let count = digits(candidate).len();
+    let formatted = candidate.starts_with('+') || candidate.contains([' ', '-', '.', '(', ')']);
+    formatted && (7..=15).contains(&count) && !DATE.is_match(candidate)
+}
+
+/// Compiled patterns for one set of settings.
+pub struct Redactor {
+    settings: RedactionSettings,
+    custom: Vec<Regex>,
+}
+
+fn compile(pattern: &CustomPattern) -> Result<Regex> {
+    RegexBuilder::new(&pattern.pattern)
+        .case_insensitive(true)
+        .build()
+        .with_context(|| format!("Invalid redaction pattern {:?}", pattern.name))
+}
+
+impl Redactor {
+    /// A custom pattern that doesn't compile is skipped and logged; the
+    /// other patterns and the built-in ones still apply.
+    pub fn new(settings: RedactionSettings) -> Self {
+        let custom = settings
+            .custom_patterns
+            .iter()
+            .filter(|p| p.enabled)
+            .filter_map(|p| match compile(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    eprintln!("[audioshift] Skipping redaction pattern: {:#}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { settings, custom }
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for re in &self.custom {
+            text = re.replace_all(&text, CUSTOM_MASK).into_owned();
+        }
+        if self.settings.emails {
+            text = EMAIL.replace_all(&text, EMAIL_MASK).into_owned();
+        }
+        // IBANs and cards before phone numbers, which would match their digits
+        if self.settings.ibans {
+            text = replace_valid(&IBAN, &text, iban_valid, IBAN_MASK);
+        }
+        if self.settings.card_numbers {
+            text = replace_valid(&CARD, &text, luhn_valid, CARD_MASK);
+        }
+        if self.settings.phone_numbers {
+            text = replace_phones(&text);
+        }
+        text
+    }
+
+    /// Redact every transcript a history entry holds.
+    pub fn redact_meta(&self, meta: &mut RecordingMeta) {
+        meta.text = self.redact(&meta.text);
+        meta.char_count = meta.text.chars().count();
+        if let Some(raw) = &mut meta.raw_text {
+            *raw = self.redact(raw);
+        }
+        if let Some(title) = &mut meta.window_title {
+            *title = self.redact(title);
+        }
+        if let Some(polish) = &mut meta.polish {
+            polish.original_text = self.redact(&polish.original_text);
+        }
+        if let Some(refinement) = &mut meta.refinement {
+            refinement.draft_text = self.redact(&refinement.draft_text);
+            for span in &mut refinement.diff {
+                let (DiffSpan::Equal(text) | DiffSpan::Insert(text) | DiffSpan::Delete(text)) = span;
+                *text = self.redact(text);
+            }
+        }
+    }
+}
+
+fn replace_valid(re: &Regex, text: &str, valid: fn(&str) -> bool, mask: &str) -> String {
+    re.replace_all(text, |caps: &Captures| {
+        if valid(&caps[0]) {
+            mask.to_string()
+        } else {
+            caps[0].to_string()
+        }
+    })
+    .into_owned()
+}
+
+/// Phone numbers are free-form, so a match is rejected when it's part of a
+/// word or too short or long to dial.
+fn replace_phones(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for m in PHONE.find_iter(text) {
+        let attached = text[..m.start()].chars().next_back().is_some_and(|c| c.is_alphanumeric())
+            || text[m.end()..].chars().next().is_some_and(|c| c.is_alphanumeric());
+        if attached || !phone_like(m.as_str()) {
+            continue;
+        }
+        out.push_str(&text[last..m.start()]);
+        out.push_str(PHONE_MASK);
+        last = m.end();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+pub fn load_settings(app: &AppHandle) -> RedactionSettings {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Reject custom patterns without a name or that don't compile.
+pub fn validate(settings: &RedactionSettings) -> Result<()> {
+    for pattern in &settings.custom_patterns {
+        anyhow::ensure!(!pattern.name.trim().is_empty(), "Redaction patterns need a name");
+        compile(pattern)?;
+    }
+    Ok(())
+}
+
+pub fn save_settings(app: &AppHandle, settings: &RedactionSettings) -> Result<()> {
+    validate(settings)?;
+    let store = app.store("settings.json")?;
+    store.set(SETTINGS_KEY, serde_json::to_value(settings)?);
+    Ok(())
+}
+
+fn redactor_if(app: &AppHandle, wanted: impl Fn(&RedactionSettings) -> bool) -> Option<Redactor> {
+    let settings = load_settings(app);
+    wanted(&settings).then(|| Redactor::new(settings))
+}
+
+/// Redactor for history entries, if history redaction is on.
+pub fn for_history(app: &AppHandle) -> Option<Redactor> {
+    redactor_if(app, |s| s.redact_history)
+}
+
+/// Redact `text` before it's pasted, if pasted-text redaction is on.
+pub fn pasted(app: &AppHandle, text: String) -> String {
+    match redactor_if(app, |s| s.redact_pasted) {
+        Some(redactor) => redactor.redact(&text),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor(custom_patterns: Vec<CustomPattern>) -> Redactor {
+        Redactor::new(RedactionSettings { custom_patterns, ..Default::default() })
+    }
+
+    #[test]
+    fn masks_formatted_phone_numbers() {
+        let r = redactor(Vec::new());
+        assert_eq!(r.redact("Call +49 30 1234567 today"), "Call [phone] today");
+        assert_eq!(r.redact("Call 555-123-4567."), "Call [phone].");
+        assert_eq!(r.redact("Call (555) 123 4567"), "Call [phone]");
+    }
+
+    #[test]
+    fn keeps_plain_numbers_and_dates() {
+        let r = redactor(Vec::new());
+        for text in ["It costs 1500000 dollars", "Order 12345678 shipped", "Due 2024-03-15"] {
+            assert_eq!(r.redact(text), text);
+        }
+    }
+
+    #[test]
+    fn invalid_custom_pattern_keeps_other_redaction() {
+        let pattern = |name: &str, pattern: &str| CustomPattern {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            enabled: true,
+        };
+        let settings = RedactionSettings {
+            custom_patterns: vec![pattern("broken", "(unclosed"), pattern("project", r"project \w+")],
+            ..Default::default()
+        };
+        assert!(validate(&settings).is_err());
+
+        let r = Redactor::new(settings);
+        assert_eq!(
+            r.redact("Mail jane@example.com about Project Falcon"),
+            "Mail [email] about [redacted]"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Two-pass transcription: the live model's draft is pasted right away, then
+//! a larger model re-transcribes the same audio in the background. The
+//! refined text replaces the draft in history (the draft and a word diff are
+//! kept) and is offered through the `transcription-refined` event and the
+//! tray's "Paste Refined Transcript" item.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+use crate::paster::{PasteChord, PasteMode};
+use crate::{file_storage, model_registry, paster, postprocess, redact, transcriber};
+
+/// How a history entry's text was refined by the second pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Refinement {
+    /// Post-processed text from the first, fast pass.
+    pub draft_text: String,
+    pub draft_model_id: String,
+    pub processing_time_ms: u64,
+    /// Word-level changes from the draft to the refined text.
+    pub diff: Vec<DiffSpan>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "text", rename_all = "lowercase")]
+pub enum DiffSpan {
+    Equal(String),
+    Insert(String),
+    Delete(String),
+}
+
+/// Payload of the `transcription-refined` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefinedTranscript {
+    /// History entry that was updated, if history is enabled.
+    pub entry_id: Option<String>,
+    pub draft: String,
+    pub text: String,
+    pub model_id: String,
+    pub diff: Vec<DiffSpan>,
+    /// How the draft's app profile delivers text, if it overrides the settings.
+    #[serde(skip)]
+    pub paste_mode: Option<PasteMode>,
+    #[serde(skip)]
+    pub paste_chord: Option<PasteChord>,
+}
+
+/// Most recent refinement, pasted by the tray action.
+static LAST_REFINED: Mutex<Option<RefinedTranscript>> = Mutex::new(None);
+
+/// Second-pass model (`twoPassModel`), or `None` when two-pass is off.
+pub fn two_pass_model(app: &AppHandle) -> Option<String> {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get("twoPassModel"))
+        .and_then(|v| v.as_str().map(String::from))
+        .filter(|id| model_registry::find_model(id).is_some())
+}
+
+/// The audio and settings the draft was made with.
+pub struct Draft {
+    pub entry_id: Option<String>,
+    pub samples: Vec<f32>,
+    pub text: String,
+    pub model_id: String,
+    pub translate: bool,
+    pub prompt: Option<String>,
+    /// Stage settings, snippets included, the draft was post-processed with.
+    pub pipeline: postprocess::Pipeline,
+    /// Language, mode and placeholder values the draft was post-processed with.
+    pub context: postprocess::Context,
+    pub paste_mode: Option<PasteMode>,
+    pub paste_chord: Option<PasteChord>,
+}
+
+/// Re-transcribe `draft` with the two-pass model in the background, if one
+/// is configured, downloaded and different from the draft's model.
+pub fn spawn_refinement(app: &AppHandle, draft: Draft) {
+    let Some(model_id) = two_pass_model(app) else {
+        return;
+    };
+    if model_id == draft.model_id {
+        return;
+    }
+    // A background pass never triggers a multi-gigabyte download
+    if !model_registry::model_ready(&model_id) {
+        eprintln!("[audioshift] Two-pass model {} is not downloaded, skipping refinement", model_id);
+        return;
+    }
+    if draft.translate
+        && !model_registry::find_model(&model_id).is_some_and(|m| m.capabilities.translate)
+    {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let started = std::time::Instant::now();
+        let result = transcriber::transcribe_in_background(
+            &model_id,
+            draft.samples,
+            draft.context.language.clone(),
+            draft.translate,
+            draft.prompt,
+        )
+        .await;
+        let raw_text = match result {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("[audioshift] Refinement with {} failed: {}", model_id, e);
+                return;
+            }
+        };
+        // Same settings and snippet values as the draft; the LLM polish isn't
+        // repeated, so the refined text is the second model's own
+        let text = draft.pipeline.run(&raw_text, &draft.context);
+        let text = redact::pasted(&app, text);
+        if text.is_empty() || text == draft.text {
+            return;
+        }
+
+        let diff = word_diff(&draft.text, &text);
+        if let Some(id) = &draft.entry_id {
+            let refinement = Refinement {
+                draft_text: draft.text.clone(),
+                draft_model_id: draft.model_id,
+                processing_time_ms: started.elapsed().as_millis() as u64,
+                diff: diff.clone(),
+            };
+            let redactor = redact::for_history(&app);
+            let updated = file_storage::update_meta(id, |meta| {
+                meta.char_count = text.chars().count();
+                meta.text = text.clone();
+                meta.raw_text = (raw_text != text).then(|| raw_text.clone());
+                meta.model_id = model_id.clone();
+                meta.polish = None;
+                meta.refinement = Some(refinement);
+                if let Some(redactor) = &redactor {
+                    redactor.redact_meta(meta);
+                }
+            });
+            match updated {
+                Ok(_) => {
+                    let _ = app.emit("history-updated", ());
+                }
+                Err(e) => eprintln!("[audioshift] Failed to store refinement for {}: {}", id, e),
+            }
+        }
+
+        let refined = RefinedTranscript {
+            entry_id: draft.entry_id,
+            draft: draft.text,
+            text,
+            model_id,
+            diff,
+            paste_mode: draft.paste_mode,
+            paste_chord: draft.paste_chord,
+        };
+        *LAST_REFINED.lock() = Some(refined.clone());
+        let _ = app.emit("transcription-refined", &refined);
+    });
+}
+
+pub fn last_refined() -> Option<RefinedTranscript> {
+    LAST_REFINED.lock().clone()
+}
+
+/// Paste, copy or type the most recent refined transcript, the way the
+/// draft's app profile delivers text or else in `mode`. Returns false if
+/// there is none.
+pub fn apply_last_refined(app: &AppHandle, mode: PasteMode) -> anyhow::Result<bool> {
+    let Some(refined) = last_refined() else {
+        return Ok(false);
+    };
+    let mode = refined.paste_mode.unwrap_or(mode);
+    paster::output(app, &refined.text, mode, refined.paste_chord)?;
+    Ok(true)
+}
+
+/// Word-level diff from `old` to `new` (longest common subsequence), with
+/// runs of the same operation merged into one span.
+pub fn word_diff(old: &str, new: &str) -> Vec<DiffSpan> {
+    let a: Vec<&str> = old.split_whitespace().collect();
+    let b: Vec<&str> = new.split_whitespace().collect();
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut spans: Vec<DiffSpan> = Vec::new();
+    let mut push = |span: DiffSpan| {
+        let merged = match (spans.last_mut(), &span) {
+            (Some(DiffSpan::Equal(t)), DiffSpan::Equal(w))
+            | (Some(DiffSpan::Insert(t)), DiffSpan::Insert(w))
+            | (Some(DiffSpan::Delete(t)), DiffSpan::Delete(w)) => {
+                t.push(' ');
+                t.push_str(w);
+                true
+            }
+            _ => false,
+        };
+        if !merged {
+            spans.push(span);
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            push(DiffSpan::Equal(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            push(DiffSpan::Delete(a[i].to_string()));
+            i += 1;
+        } else {
+            push(DiffSpan::Insert(b[j].to_string()));
+            j += 1;
+        }
+    }
+    for word in &a[i..] {
+        push(DiffSpan::Delete(word.to_string()));
+    }
+    for word in &b[j..] {
+        push(DiffSpan::Insert(word.to_string()));
+    }
+    spans
+}
@@ -0,0 +1,40 @@
+//! Helpers shared by the user-edited lists kept in the settings store:
+//! replacement rules, snippets, profiles and redaction patterns.
+
+use std::collections::HashSet;
+
+/// Serde default for `enabled` flags, so entries written without one are on.
+pub fn default_true() -> bool {
+    true
+}
+
+/// Give every item without an id, or with an id already used earlier in
+/// the list, a fresh `<prefix>-<millis>-<index>` id.
+pub fn assign_ids<T>(items: &mut [T], prefix: &str, id: impl Fn(&mut T) -> &mut String) {
+    let mut seen = HashSet::new();
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    for (i, item) in items.iter_mut().enumerate() {
+        let id = id(item);
+        if id.is_empty() || !seen.insert(id.clone()) {
+            *id = format!("{}-{}-{}", prefix, stamp, i);
+            seen.insert(id.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_and_missing_ids_are_replaced() {
+        let mut ids = vec!["a".to_string(), "a".to_string(), String::new()];
+        assign_ids(&mut ids, "rule", |id| id);
+        assert_eq!(ids[0], "a");
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 3);
+        assert!(ids[1].starts_with("rule-") && ids[2].starts_with("rule-"));
+    }
+}
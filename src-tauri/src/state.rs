@@ -5,6 +5,8 @@ use tauri::menu::MenuItem;
 use tauri::tray::TrayIcon;
 use tokio::sync::watch;
 
+use crate::postprocess::Mode;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Status {
@@ -35,9 +37,12 @@ pub struct AppState {
     status: Mutex<Status>,
     pub audio_buffer: Arc<Mutex<Vec<f32>>>,
     hotkey: Mutex<String>,
+    /// Mode chosen by the hotkey that started the current recording.
+    dictation_mode: Mutex<Option<Mode>>,
     tray: Mutex<Option<TrayIcon>>,
     tray_status_item: Mutex<Option<MenuItem<tauri::Wry>>>,
     tray_updates_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    tray_refined_item: Mutex<Option<MenuItem<tauri::Wry>>>,
     animation_tx: watch::Sender<TrayAnimation>,
     animation_rx: Mutex<Option<watch::Receiver<TrayAnimation>>>,
 }
@@ -54,9 +59,11 @@ impl AppState {
             } else {
                 "Ctrl+Shift+Space"
             }.to_string()),
+            dictation_mode: Mutex::new(None),
             tray: Mutex::new(None),
             tray_status_item: Mutex::new(None),
             tray_updates_item: Mutex::new(None),
+            tray_refined_item: Mutex::new(None),
             animation_tx,
             animation_rx: Mutex::new(Some(animation_rx)),
         }
@@ -78,6 +85,15 @@ impl AppState {
         *self.hotkey.lock() = hotkey;
     }
 
+    pub fn set_dictation_mode(&self, mode: Option<Mode>) {
+        *self.dictation_mode.lock() = mode;
+    }
+
+    /// Mode for the recording being stopped, cleared for the next one.
+    pub fn take_dictation_mode(&self) -> Option<Mode> {
+        self.dictation_mode.lock().take()
+    }
+
     pub fn set_tray(&self, tray: TrayIcon, status_item: MenuItem<tauri::Wry>) {
         *self.tray.lock() = Some(tray);
         *self.tray_status_item.lock() = Some(status_item);
@@ -99,6 +115,14 @@ impl AppState {
         self.tray_updates_item.lock().clone()
     }
 
+    pub fn set_tray_refined_item(&self, item: MenuItem<tauri::Wry>) {
+        *self.tray_refined_item.lock() = Some(item);
+    }
+
+    pub fn tray_refined_item(&self) -> Option<MenuItem<tauri::Wry>> {
+        self.tray_refined_item.lock().clone()
+    }
+
     pub fn set_animation(&self, anim: TrayAnimation) {
         let _ = self.animation_tx.send(anim);
     }
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::{file_storage, model_registry};
+
+/// Extra headroom required on top of a model's size before downloading.
+const DOWNLOAD_HEADROOM_BYTES: u64 = 100_000_000;
+
+/// Free bytes on the filesystem holding `path`. Walks up to the nearest
+/// existing ancestor so it works for directories that don't exist yet.
+pub fn available_space(path: &Path) -> Result<u64> {
+    let mut dir = path;
+    while !dir.exists() {
+        dir = dir.parent().context("No existing parent directory")?;
+    }
+    free_bytes(dir)
+}
+
+#[cfg(unix)]
+fn free_bytes(dir: &Path) -> Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(dir.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to query free space for {:?}", dir));
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn free_bytes(dir: &Path) -> Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available: u64 = 0;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available as *mut u64), None, None) }
+        .with_context(|| format!("Failed to query free space for {:?}", dir))?;
+    Ok(available)
+}
+
+/// Fail early if the models directory can't fit the rest of `model_id`.
+pub fn check_download_space(model_id: &str) -> Result<()> {
+    let def = model_registry::find_model(model_id)
+        .with_context(|| format!("Unknown model: {}", model_id))?;
+    let dir = model_registry::model_dir(model_id);
+    let remaining = def
+        .approx_bytes
+        .saturating_sub(model_registry::model_disk_size(model_id));
+    let needed = remaining + DOWNLOAD_HEADROOM_BYTES;
+    let free = available_space(&dir)?;
+    if free < needed {
+        anyhow::bail!(
+            "Not enough disk space to download {}: needs {}, only {} free in {}",
+            def.name,
+            model_registry::size_label(needed),
+            model_registry::size_label(free),
+            model_registry::models_base_dir().display()
+        );
+    }
+    Ok(())
+}
+
+/// Remove leftovers of a cancelled or rejected download: `.part` files, and
+/// the model directory itself if nothing complete is left in it.
+pub fn cleanup_partial_download(model_id: &str) {
+    let dir = model_registry::model_dir(model_id);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        if entry.path().extension().is_some_and(|ext| ext == "part") {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+    if model_registry::model_state(model_id) == model_registry::ModelState::Missing {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+/// Total size of all files below `path`.
+pub fn dir_size(path: &Path) -> u64 {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| match e.file_type() {
+                    Ok(t) if t.is_dir() => dir_size(&e.path()),
+                    _ => e.metadata().map(|m| m.len()).unwrap_or(0),
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirUsage {
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub models_dir: String,
+    pub models_bytes: u64,
+    pub models: Vec<DirUsage>,
+    /// Directories in the models folder that don't belong to any catalog model.
+    pub orphaned: Vec<DirUsage>,
+    pub recordings_dir: String,
+    pub recordings_bytes: u64,
+    pub recordings_count: usize,
+    pub free_bytes: Option<u64>,
+}
+
+/// Model directories below `path`. Hidden directories, such as the
+/// `.import-*` staging directories of a bundle import, are skipped.
+fn subdirs(path: &Path) -> Vec<(String, PathBuf)> {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+                .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+                .filter(|(name, _)| !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn storage_report() -> StorageReport {
+    let models_dir = model_registry::models_base_dir();
+    let catalog = model_registry::catalog();
+
+    let mut models = Vec::new();
+    let mut orphaned = Vec::new();
+    for (name, path) in subdirs(&models_dir) {
+        let usage = DirUsage {
+            bytes: dir_size(&path),
+            path: path.to_string_lossy().to_string(),
+            name,
+        };
+        if catalog.models.iter().any(|m| m.id == usage.name) {
+            models.push(usage);
+        } else {
+            orphaned.push(usage);
+        }
+    }
+
+    let recordings_dir = file_storage::recordings_dir();
+    let recordings = subdirs(&recordings_dir);
+
+    StorageReport {
+        models_bytes: models.iter().chain(&orphaned).map(|u| u.bytes).sum(),
+        models,
+        orphaned,
+        recordings_bytes: dir_size(&recordings_dir),
+        recordings_count: recordings.len(),
+        free_bytes: available_space(&models_dir).ok(),
+        models_dir: models_dir.to_string_lossy().to_string(),
+        recordings_dir: recordings_dir.to_string_lossy().to_string(),
+    }
+}
+
+/// Delete model directories that no catalog model owns.
+/// Returns the names of the removed directories.
+pub fn remove_orphaned_models() -> Result<Vec<String>> {
+    let catalog = model_registry::catalog();
+    let mut removed = Vec::new();
+    for (name, path) in subdirs(&model_registry::models_base_dir()) {
+        if catalog.models.iter().any(|m| m.id == name) {
+            continue;
+        }
+        std::fs::remove_dir_all(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+        removed.push(name);
+    }
+    Ok(removed)
+}
@@ -3,51 +3,121 @@ use parakeet_rs::{ExecutionConfig, ParakeetTDT, TimestampMode, Transcriber};
 #[cfg(windows)]
 use parakeet_rs::ExecutionProvider;
 use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
 use tauri::Emitter;
+use tokio::sync::watch;
+use tauri_plugin_store::StoreExt;
 use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
 
-use crate::model_registry::{self, Engine, DEFAULT_MODEL_ID};
+use crate::{downloader, hardware};
+use crate::model_registry::{self, Engine};
+use crate::storage;
 
-static PARAKEET_MODEL: Mutex<Option<ParakeetTDT>> = Mutex::new(None);
-/// (model_id, WhisperContext) — we store the id to know which model is loaded.
-static WHISPER_CTX: Mutex<Option<(String, WhisperContext)>> = Mutex::new(None);
-static DOWNLOAD_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+/// Loaded models across both engines, least recently used first.
+static MODEL_CACHE: Mutex<Vec<CachedModel>> = Mutex::new(Vec::new());
+/// Downloads currently running, keyed by model id.
+static DOWNLOADS: LazyLock<Mutex<HashMap<String, ActiveDownload>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Shared handle to a running model download. Other callers asking for the
+/// same model wait on `done` instead of starting a second download.
+#[derive(Clone)]
+struct ActiveDownload {
+    model_id: String,
+    cancel: Arc<AtomicBool>,
+    downloaded: Arc<AtomicU64>,
+    total: u64,
+    done: watch::Receiver<Option<Result<(), String>>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadStatus {
+    pub model_id: String,
+    pub downloaded: u64,
+    pub total: u64,
+    pub cancelling: bool,
+}
 
 pub fn is_downloading() -> bool {
-    DOWNLOAD_IN_PROGRESS.load(Ordering::Relaxed)
+    !DOWNLOADS.lock().is_empty()
+}
+
+pub fn download_status() -> Vec<DownloadStatus> {
+    DOWNLOADS
+        .lock()
+        .iter()
+        .map(|(id, d)| DownloadStatus {
+            model_id: id.clone(),
+            downloaded: d.downloaded.load(Ordering::Relaxed),
+            total: d.total,
+            cancelling: d.cancel.load(Ordering::Relaxed),
+        })
+        .collect()
+}
+
+/// Request cancellation of a running download. Returns false if the model
+/// isn't downloading. Partial files are discarded once the download stops.
+pub fn cancel_download(model_id: &str) -> bool {
+    match DOWNLOADS.lock().get(model_id) {
+        Some(d) => {
+            d.cancel.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
 }
 
 // --- Download / delete ---
 
+/// Download-related settings: `modelMirrorUrl` rewrites catalog URLs onto a
+/// mirror, `downloadProxy` routes requests through an explicit HTTP(S) proxy.
+pub struct DownloadSettings {
+    pub mirror: Option<String>,
+    pub proxy: Option<String>,
+}
+
+pub fn download_settings(app: &tauri::AppHandle) -> DownloadSettings {
+    let store = app.store("settings.json").ok();
+    let get = |key: &str| {
+        store
+            .as_ref()
+            .and_then(|s| s.get(key))
+            .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+            .filter(|s| !s.is_empty())
+    };
+    DownloadSettings {
+        mirror: get("modelMirrorUrl"),
+        proxy: get("downloadProxy"),
+    }
+}
+
+pub fn download_client(app: &tauri::AppHandle) -> Result<reqwest::Client> {
+    downloader::build_client(download_settings(app).proxy.as_deref())
+}
+
 async fn download_file(
+    client: &reqwest::Client,
     url: &str,
     dest: &Path,
+    download: &ActiveDownload,
     app: &tauri::AppHandle,
     label: &str,
-    model_id: &str,
-    approx_total: u64,
     cumulative_offset: u64,
 ) -> Result<u64> {
-    use futures_util::StreamExt;
-    use tokio::io::AsyncWriteExt;
-
-    let client = reqwest::Client::new();
-    let resp = client.get(url).send().await?.error_for_status()?;
-    let total = resp.content_length().unwrap_or(0);
-
-    let mut stream = resp.bytes_stream();
-    let mut file = tokio::fs::File::create(dest).await?;
-    let mut downloaded: u64 = 0;
+    let approx_total = download.total;
     let mut last_overall_pct: u32 =
         ((cumulative_offset as f64 / approx_total as f64) * 100.0).min(99.0) as u32;
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        file.write_all(&chunk).await?;
-        downloaded += chunk.len() as u64;
-
+    let mut file_bytes: u64 = 0;
+    downloader::download_file(client, url, dest, &download.cancel, |downloaded, total| {
+        file_bytes = downloaded;
+        download
+            .downloaded
+            .store(cumulative_offset + downloaded, Ordering::Relaxed);
         let progress = if total > 0 {
             (downloaded as f64 / total as f64 * 100.0) as u32
         } else {
@@ -63,7 +133,7 @@ async fn download_file(
                 "model-download-progress",
                 serde_json::json!({
                     "file": label,
-                    "modelId": model_id,
+                    "modelId": download.model_id,
                     "progress": progress,
                     "downloaded": downloaded,
                     "total": total,
@@ -73,13 +143,17 @@ async fn download_file(
                 }),
             );
         }
-    }
+    })
+    .await?;
 
-    file.flush().await?;
-    Ok(downloaded)
+    Ok(file_bytes)
 }
 
-async fn do_ensure_model(app: &tauri::AppHandle, model_id: &str) -> Result<()> {
+async fn do_ensure_model(
+    app: &tauri::AppHandle,
+    model_id: &str,
+    download: &ActiveDownload,
+) -> Result<()> {
     let def = model_registry::find_model(model_id)
         .with_context(|| format!("Unknown model: {}", model_id))?;
 
@@ -87,17 +161,17 @@ async fn do_ensure_model(app: &tauri::AppHandle, model_id: &str) -> Result<()> {
         return Ok(());
     }
 
+    storage::check_download_space(model_id)?;
+
     let dir = model_registry::model_dir(model_id);
     tokio::fs::create_dir_all(&dir).await?;
 
-    // Collect files that still need downloading
-    let mut files_to_download = Vec::new();
-    for file in def.files {
-        let dest_name = model_registry::file_dest_name(file);
-        if dir.join(dest_name).exists() {
-            continue;
-        }
-        files_to_download.push(file);
+    // Collect files that are missing or failed verification; intact files are kept
+    let files_to_download = model_registry::files_needing_download(model_id);
+    for file in &files_to_download {
+        let path = dir.join(model_registry::file_dest_name(file));
+        let _ = tokio::fs::remove_file(model_registry::corrupt_marker(&path)).await;
+        let _ = tokio::fs::remove_file(&path).await;
     }
 
     let _ = app.emit(
@@ -112,32 +186,39 @@ async fn do_ensure_model(app: &tauri::AppHandle, model_id: &str) -> Result<()> {
         }),
     );
 
+    let settings = download_settings(app);
+    let client = downloader::build_client(settings.proxy.as_deref())?;
+
     let mut cumulative_offset: u64 = 0;
-    for file in &files_to_download {
-        let url_filename = file.url.rsplit('/').next().unwrap_or("file");
-        let download_dest = dir.join(url_filename);
+    for file in files_to_download {
+        let url_filename = file.url.rsplit('/').next().unwrap_or("file").to_string();
+        // Download straight to the final name; the downloader stages the body
+        // in a `.part` file, so a partial download is never mistaken for a
+        // finished one and can be resumed on the next attempt.
+        let dest = dir.join(model_registry::file_dest_name(&file));
+
+        let url = match &settings.mirror {
+            Some(mirror) => downloader::mirror_url(&file.url, mirror),
+            None => file.url.clone(),
+        };
 
-        let bytes_downloaded = download_file(
-            file.url,
-            &download_dest,
+        let file_bytes = download_file(
+            &client,
+            &url,
+            &dest,
+            download,
             app,
-            url_filename,
-            model_id,
-            def.approx_bytes,
+            &url_filename,
             cumulative_offset,
         )
         .await
         .with_context(|| format!("Failed to download {}", url_filename))?;
 
-        cumulative_offset += bytes_downloaded;
+        let mid = model_id.to_string();
+        tokio::task::spawn_blocking(move || model_registry::verify_downloaded_file(&mid, &file))
+            .await??;
 
-        // Rename if needed
-        if let Some(rename_to) = file.rename_to {
-            let final_path = dir.join(rename_to);
-            if download_dest != final_path && !final_path.exists() {
-                tokio::fs::rename(&download_dest, &final_path).await?;
-            }
-        }
+        cumulative_offset += file_bytes;
     }
 
     let _ = app.emit(
@@ -155,45 +236,105 @@ async fn do_ensure_model(app: &tauri::AppHandle, model_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Make sure a model is on disk, downloading it if needed. If the same model
+/// is already being downloaded, wait for that download instead of starting
+/// another one; downloads of different models run concurrently.
 pub async fn ensure_model(app: &tauri::AppHandle, model_id: &str) -> Result<()> {
     if model_registry::model_ready(model_id) {
         return Ok(());
     }
+    let def = model_registry::find_model(model_id)
+        .with_context(|| format!("Unknown model: {}", model_id))?;
 
-    if DOWNLOAD_IN_PROGRESS
-        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
-        .is_err()
-    {
-        return Ok(());
+    // Either join a download already in flight or register ours, without
+    // holding the lock across an await
+    let started = {
+        let mut downloads = DOWNLOADS.lock();
+        match downloads.get(model_id) {
+            Some(existing) => Err(existing.clone()),
+            None => {
+                let (tx, rx) = watch::channel(None);
+                let download = ActiveDownload {
+                    model_id: model_id.to_string(),
+                    cancel: Arc::new(AtomicBool::new(false)),
+                    downloaded: Arc::new(AtomicU64::new(0)),
+                    total: def.approx_bytes,
+                    done: rx,
+                };
+                downloads.insert(model_id.to_string(), download.clone());
+                Ok((download, tx))
+            }
+        }
+    };
+    let (download, tx) = match started {
+        Ok(started) => started,
+        Err(existing) => return wait_for_download(existing).await,
+    };
+    let registered = Registered(model_id);
+
+    let result = do_ensure_model(app, model_id, &download).await;
+    let cancelled = result
+        .as_ref()
+        .err()
+        .is_some_and(|e| e.is::<downloader::Cancelled>());
+    let mismatch = result
+        .as_ref()
+        .err()
+        .is_some_and(|e| e.is::<model_registry::ChecksumMismatch>());
+    if cancelled || mismatch {
+        // Drop unfinished files; completed, verified files are kept. After
+        // a network error they stay so the next attempt resumes.
+        let mid = model_id.to_string();
+        let _ = tokio::task::spawn_blocking(move || storage::cleanup_partial_download(&mid)).await;
+    }
+    if cancelled {
+        let _ = app.emit("model-download-cancelled", model_id);
     }
 
-    let result = do_ensure_model(app, model_id).await;
-    DOWNLOAD_IN_PROGRESS.store(false, Ordering::SeqCst);
+    drop(registered);
+    let _ = tx.send(Some(result.as_ref().map(|_| ()).map_err(|e| format!("{:#}", e))));
     result
 }
 
+/// Removes a download from `DOWNLOADS` when dropped, so a panic or a dropped
+/// future can't leave the model looking like it's downloading forever.
+/// Waiters see the sender go away and fail with "Download was interrupted".
+struct Registered<'a>(&'a str);
+
+impl Drop for Registered<'_> {
+    fn drop(&mut self) {
+        DOWNLOADS.lock().remove(self.0);
+    }
+}
+
+async fn wait_for_download(mut download: ActiveDownload) -> Result<()> {
+    let result = download
+        .done
+        .wait_for(|r| r.is_some())
+        .await
+        .map_err(|_| anyhow::anyhow!("Download was interrupted"))?
+        .clone()
+        .unwrap_or(Ok(()));
+    result.map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Stop a running download of the model and unload it, so its directory can
+/// be removed or replaced without the download recreating it.
+pub async fn release_model(model_id: &str) {
+    let running = DOWNLOADS.lock().get(model_id).cloned();
+    if let Some(download) = running {
+        download.cancel.store(true, Ordering::SeqCst);
+        let _ = wait_for_download(download).await;
+    }
+    unload_model(model_id);
+}
+
 pub async fn delete_model(model_id: &str) -> Result<()> {
-    let def = model_registry::find_model(model_id);
-    if def.is_none() {
+    if model_registry::find_model(model_id).is_none() {
         anyhow::bail!("Unknown model: {}", model_id);
     }
-    let engine = def.unwrap().engine;
 
-    // Unload if this model is currently loaded
-    match engine {
-        Engine::Parakeet => {
-            let mut lock = PARAKEET_MODEL.lock();
-            *lock = None;
-        }
-        Engine::Whisper => {
-            let mut lock = WHISPER_CTX.lock();
-            if let Some((ref id, _)) = *lock {
-                if id == model_id {
-                    *lock = None;
-                }
-            }
-        }
-    }
+    release_model(model_id).await;
 
     let dir = model_registry::model_dir(model_id);
     if dir.exists() {
@@ -202,15 +343,149 @@ pub async fn delete_model(model_id: &str) -> Result<()> {
     Ok(())
 }
 
-// --- Parakeet engine ---
+// --- Model cache ---
 
-fn execution_provider_label() -> &'static str {
-    #[cfg(windows)]
-    { "DirectML" }
-    #[cfg(not(windows))]
-    { "CPU" }
+/// A loaded model of either engine.
+enum LoadedModel {
+    Parakeet(ParakeetTDT),
+    Whisper(WhisperContext),
+}
+
+struct CachedModel {
+    info: LoadedModelInfo,
+    model: Arc<Mutex<LoadedModel>>,
+}
+
+/// Bounds for the model cache: at most `max_models` resident models and,
+/// if set, at most `max_bytes` of estimated memory.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheLimits {
+    pub max_models: usize,
+    pub max_bytes: Option<u64>,
+}
+
+pub const DEFAULT_CACHE_LIMITS: CacheLimits = CacheLimits {
+    max_models: 2,
+    max_bytes: None,
+};
+
+static CACHE_LIMITS: Mutex<CacheLimits> = Mutex::new(DEFAULT_CACHE_LIMITS);
+/// Serializes model loads so two callers never load the same model twice.
+static LOAD_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn cache_limits() -> CacheLimits {
+    *CACHE_LIMITS.lock()
+}
+
+pub fn set_cache_limits(limits: CacheLimits) {
+    *CACHE_LIMITS.lock() = CacheLimits {
+        max_models: limits.max_models.max(1),
+        ..limits
+    };
+    evict_over_limits(None, None);
+}
+
+/// Drop least recently used models until the cache fits its limits.
+/// `keep` is never evicted (the model that was just loaded). `reserve` makes
+/// room for a model about to be loaded with that estimated size, so its load
+/// doesn't push memory use past the budget.
+fn evict_over_limits(keep: Option<&str>, reserve: Option<u64>) {
+    let limits = cache_limits();
+    let (extra_models, extra_bytes) = match reserve {
+        Some(bytes) => (1, bytes),
+        None => (0, 0),
+    };
+    let mut cache = MODEL_CACHE.lock();
+    loop {
+        let total: u64 = cache.iter().map(|c| c.info.memory_bytes).sum::<u64>() + extra_bytes;
+        let over_count = cache.len() + extra_models > limits.max_models;
+        let over_bytes = limits.max_bytes.is_some_and(|max| total > max);
+        if !over_count && !over_bytes {
+            break;
+        }
+        // Front of the list is the least recently used
+        let Some(pos) = cache.iter().position(|c| Some(c.info.model_id.as_str()) != keep) else {
+            break;
+        };
+        let evicted = cache.remove(pos);
+        eprintln!("[audioshift] Evicted model from cache: {}", evicted.info.model_id);
+    }
+}
+
+/// Fetch a model from the cache, loading it if needed, and mark it as most
+/// recently used.
+fn get_or_load(model_id: &str) -> Result<Arc<Mutex<LoadedModel>>> {
+    if let Some(model) = touch_cached(model_id) {
+        return Ok(model);
+    }
+
+    let _guard = LOAD_LOCK.lock();
+    if let Some(model) = touch_cached(model_id) {
+        return Ok(model);
+    }
+
+    let def = model_registry::find_model(model_id)
+        .with_context(|| format!("Unknown model: {}", model_id))?;
+    let expected_bytes = def
+        .performance
+        .as_ref()
+        .map(|p| p.ram_bytes)
+        .unwrap_or_else(|| model_registry::model_disk_size(model_id));
+    evict_over_limits(None, Some(expected_bytes));
+
+    let started = std::time::Instant::now();
+    let rss_before = hardware::process_resident_bytes();
+    let (model, backend) = match def.engine {
+        Engine::Parakeet => {
+            let (m, backend) = load_parakeet(model_id)?;
+            (LoadedModel::Parakeet(m), backend)
+        }
+        Engine::Whisper => {
+            let (ctx, backend) = load_whisper(model_id)?;
+            (LoadedModel::Whisper(ctx), backend)
+        }
+    };
+    let info = loaded_info(model_id, def.engine, backend, started, rss_before);
+    let model = Arc::new(Mutex::new(model));
+    MODEL_CACHE.lock().push(CachedModel {
+        info,
+        model: model.clone(),
+    });
+    evict_over_limits(Some(model_id), None);
+    Ok(model)
+}
+
+fn touch_cached(model_id: &str) -> Option<Arc<Mutex<LoadedModel>>> {
+    let mut cache = MODEL_CACHE.lock();
+    let pos = cache.iter().position(|c| c.info.model_id == model_id)?;
+    let mut entry = cache.remove(pos);
+    entry.info.last_used_at = now_millis();
+    let model = entry.model.clone();
+    cache.push(entry);
+    Some(model)
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
 }
 
+/// GPU backend an engine is built with on this platform, if any:
+/// whisper.cpp uses Metal on macOS, Parakeet's ONNX runtime uses DirectML
+/// on Windows. Everything else runs on the CPU.
+pub fn gpu_backend(engine: Engine) -> Option<&'static str> {
+    match engine {
+        Engine::Whisper if cfg!(target_os = "macos") => Some("Metal"),
+        Engine::Parakeet if cfg!(windows) => Some("DirectML"),
+        _ => None,
+    }
+}
+
+// --- Parakeet engine ---
+
 fn default_execution_config() -> Option<ExecutionConfig> {
     #[cfg(windows)]
     {
@@ -222,16 +497,11 @@ fn default_execution_config() -> Option<ExecutionConfig> {
     }
 }
 
-fn load_parakeet() -> Result<()> {
-    let mut lock = PARAKEET_MODEL.lock();
-    if lock.is_some() {
-        return Ok(());
-    }
-
-    let dir = model_registry::model_dir(DEFAULT_MODEL_ID);
+fn load_parakeet(model_id: &str) -> Result<(ParakeetTDT, &'static str)> {
+    let dir = model_registry::model_dir(model_id);
     let (model, provider) = match default_execution_config() {
         Some(config) => {
-            let label = execution_provider_label();
+            let label = gpu_backend(Engine::Parakeet).unwrap_or("CPU");
             match ParakeetTDT::from_pretrained(&dir, Some(config)) {
                 Ok(m) => (m, label),
                 Err(e) => {
@@ -249,14 +519,10 @@ fn load_parakeet() -> Result<()> {
         }
     };
     eprintln!("[audioshift] Model loaded with {} execution provider", provider);
-    *lock = Some(model);
-    Ok(())
+    Ok((model, provider))
 }
 
-fn transcribe_parakeet(samples: Vec<f32>) -> Result<String> {
-    load_parakeet()?;
-    let mut lock = PARAKEET_MODEL.lock();
-    let model = lock.as_mut().context("Parakeet model not loaded")?;
+fn transcribe_parakeet(model: &mut ParakeetTDT, samples: Vec<f32>) -> Result<String> {
     let result = model
         .transcribe_samples(samples, 16000, 1, Some(TimestampMode::Sentences))
         .map_err(|e| anyhow::anyhow!("{}", e))?;
@@ -265,20 +531,14 @@ fn transcribe_parakeet(samples: Vec<f32>) -> Result<String> {
 
 // --- Whisper engine ---
 
-fn load_whisper(model_id: &str) -> Result<()> {
-    let mut lock = WHISPER_CTX.lock();
-    if let Some((ref id, _)) = *lock {
-        if id == model_id {
-            return Ok(());
-        }
-    }
-
+fn load_whisper(model_id: &str) -> Result<(WhisperContext, &'static str)> {
     let dir = model_registry::model_dir(model_id);
     let model_path = dir.join("model.bin");
     anyhow::ensure!(model_path.exists(), "Whisper model file not found: {:?}", model_path);
 
     let mut params = WhisperContextParameters::default();
-    params.use_gpu(true);
+    let backend = gpu_backend(Engine::Whisper);
+    params.use_gpu(backend.is_some());
     params.flash_attn(true);
 
     let ctx = WhisperContext::new_with_params(
@@ -288,21 +548,16 @@ fn load_whisper(model_id: &str) -> Result<()> {
     .map_err(|e| anyhow::anyhow!("Failed to load Whisper model: {}", e))?;
 
     eprintln!("[audioshift] Whisper model loaded: {}", model_id);
-    *lock = Some((model_id.to_string(), ctx));
-    Ok(())
+    Ok((ctx, backend.unwrap_or("CPU")))
 }
 
 fn transcribe_whisper(
+    ctx: &WhisperContext,
     samples: Vec<f32>,
-    model_id: String,
     language: Option<String>,
     translate: bool,
+    prompt: Option<String>,
 ) -> Result<String> {
-    load_whisper(&model_id)?;
-
-    let lock = WHISPER_CTX.lock();
-    let (_, ctx) = lock.as_ref().context("Whisper context not loaded")?;
-
     let mut state = ctx.create_state()
         .map_err(|e| anyhow::anyhow!("Failed to create Whisper state: {}", e))?;
 
@@ -310,6 +565,10 @@ fn transcribe_whisper(
     params.set_n_threads(num_cpus::get().min(8) as i32);
     params.set_language(language.as_deref());
     params.set_translate(translate);
+    if let Some(prompt) = prompt.as_deref().filter(|p| !p.trim().is_empty()) {
+        // Vocabulary and style hints; whisper.cpp panics on interior NULs
+        params.set_initial_prompt(&prompt.replace('\0', ""));
+    }
     params.set_print_progress(false);
     params.set_print_realtime(false);
     params.set_print_timestamps(false);
@@ -332,54 +591,312 @@ fn transcribe_whisper(
     Ok(text.trim().to_string())
 }
 
+/// Transcribe with a cached model, loading it first if needed.
+fn transcribe_blocking(
+    model_id: &str,
+    samples: Vec<f32>,
+    language: Option<String>,
+    translate: bool,
+    prompt: Option<String>,
+) -> Result<String> {
+    let model = get_or_load(model_id)?;
+    let mut model = model.lock();
+    match &mut *model {
+        LoadedModel::Parakeet(m) => transcribe_parakeet(m, samples),
+        LoadedModel::Whisper(ctx) => transcribe_whisper(ctx, samples, language, translate, prompt),
+    }
+}
+
 // --- Preload ---
 
 /// Preload a model into memory in the background so the first transcription is instant.
 /// Safe to call even if the model isn't downloaded yet (just returns Ok).
 pub fn preload_model(model_id: &str) -> Result<()> {
-    let def = match model_registry::find_model(model_id) {
-        Some(d) => d,
-        None => return Ok(()),
-    };
+    if model_registry::find_model(model_id).is_none() {
+        return Ok(());
+    }
 
     if !model_registry::model_ready(model_id) {
         return Ok(());
     }
 
-    match def.engine {
-        Engine::Parakeet => load_parakeet()?,
-        Engine::Whisper => load_whisper(model_id)?,
-    }
+    get_or_load(model_id)?;
 
     eprintln!("[audioshift] Model preloaded: {}", model_id);
     Ok(())
 }
 
+// --- Runtime status ---
+
+/// A model held in memory, with how long loading took and roughly how much
+/// memory it occupies.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedModelInfo {
+    pub model_id: String,
+    pub engine: Engine,
+    /// Execution backend, e.g. "CPU", "Metal" or "DirectML".
+    pub backend: &'static str,
+    /// Unix time in milliseconds.
+    pub loaded_at: i64,
+    /// Unix time in milliseconds of the last load or transcription.
+    pub last_used_at: i64,
+    pub load_time_ms: u64,
+    /// Growth of the process' resident memory while loading, or the model's
+    /// size on disk where that can't be measured (e.g. weights living on the GPU).
+    pub memory_bytes: u64,
+}
+
+fn loaded_info(
+    model_id: &str,
+    engine: Engine,
+    backend: &'static str,
+    started: std::time::Instant,
+    rss_before: Option<u64>,
+) -> LoadedModelInfo {
+    let measured = rss_before
+        .zip(hardware::process_resident_bytes())
+        .map(|(before, after)| after.saturating_sub(before))
+        .unwrap_or(0);
+    let memory_bytes = if measured > 0 {
+        measured
+    } else {
+        model_registry::model_disk_size(model_id)
+    };
+    let now = now_millis();
+    LoadedModelInfo {
+        model_id: model_id.to_string(),
+        engine,
+        backend,
+        loaded_at: now,
+        last_used_at: now,
+        load_time_ms: started.elapsed().as_millis() as u64,
+        memory_bytes,
+    }
+}
+
+/// Loaded models, most recently used first.
+pub fn loaded_models() -> Vec<LoadedModelInfo> {
+    MODEL_CACHE.lock().iter().rev().map(|c| c.info.clone()).collect()
+}
+
+pub fn runtime_info(model_id: &str) -> Option<LoadedModelInfo> {
+    MODEL_CACHE
+        .lock()
+        .iter()
+        .find(|c| c.info.model_id == model_id)
+        .map(|c| c.info.clone())
+}
+
+/// Load a downloaded model into memory (no-op if it's already loaded).
+pub fn load_model(model_id: &str) -> Result<LoadedModelInfo> {
+    anyhow::ensure!(
+        model_registry::find_model(model_id).is_some(),
+        "Unknown model: {}",
+        model_id
+    );
+    anyhow::ensure!(
+        model_registry::model_ready(model_id),
+        "Model {} is not downloaded",
+        model_id
+    );
+    get_or_load(model_id)?;
+    runtime_info(model_id).context("Model was evicted right after loading")
+}
+
+/// Release a model's memory without touching its files. Returns false if it
+/// wasn't loaded. A transcription already using the model finishes first.
+pub fn unload_model(model_id: &str) -> bool {
+    let mut cache = MODEL_CACHE.lock();
+    let before = cache.len();
+    cache.retain(|c| c.info.model_id != model_id);
+    let unloaded = cache.len() != before;
+    if unloaded {
+        eprintln!("[audioshift] Model unloaded: {}", model_id);
+    }
+    unloaded
+}
+
+// --- Automatic routing ---
+
+/// Audio fed to language detection; Whisper decides from the first window anyway.
+const DETECT_SECONDS: usize = 5;
+
+/// How a recording made with the "auto" live model was routed.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RouteDecision {
+    /// Language the route was chosen for: detected, or the configured one.
+    pub language: Option<String>,
+    /// Detection probability, `None` when the language came from settings.
+    pub confidence: Option<f32>,
+    /// Model that produced the transcript.
+    pub model_id: String,
+    pub detection_ms: u64,
+}
+
+/// Whisper model that handles languages Parakeet doesn't (`autoWhisperModel`).
+pub fn auto_whisper_model(app: &tauri::AppHandle) -> String {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get("autoWhisperModel"))
+        .and_then(|v| v.as_str().map(String::from))
+        .filter(|id| model_registry::find_model(id).is_some_and(|m| m.engine == Engine::Whisper))
+        .unwrap_or_else(|| model_registry::DEFAULT_AUTO_WHISPER_MODEL_ID.to_string())
+}
+
+/// Smallest installed Whisper model that can detect languages. Detection
+/// only ever uses installed models so it never starts a download.
+pub fn detection_model() -> Option<String> {
+    model_registry::catalog()
+        .models
+        .iter()
+        .filter(|m| m.engine == Engine::Whisper && m.capabilities.language_detection)
+        .filter(|m| model_registry::model_ready(&m.id))
+        .min_by_key(|m| m.approx_bytes)
+        .map(|m| m.id.clone())
+}
+
+/// Model to preload for `live_model`: "auto" always needs its detection
+/// model, the transcribing model is loaded on first use.
+pub fn preload_target(live_model: &str) -> String {
+    if live_model == model_registry::AUTO_MODEL_ID {
+        detection_model().unwrap_or_else(|| model_registry::DEFAULT_MODEL_ID.to_string())
+    } else {
+        live_model.to_string()
+    }
+}
+
+fn detect_language(ctx: &WhisperContext, samples: &[f32]) -> Result<(String, f32)> {
+    let threads = num_cpus::get().min(8);
+    let mut state = ctx.create_state()
+        .map_err(|e| anyhow::anyhow!("Failed to create Whisper state: {}", e))?;
+    state.pcm_to_mel(samples, threads)
+        .map_err(|e| anyhow::anyhow!("Failed to compute mel spectrogram: {}", e))?;
+    let (lang_id, probs) = state.lang_detect(0, threads)
+        .map_err(|e| anyhow::anyhow!("Language detection failed: {}", e))?;
+    let lang = whisper_rs::get_lang_str(lang_id).context("Unknown language id")?;
+    let confidence = probs.get(lang_id as usize).copied().unwrap_or(0.0);
+    Ok((lang.to_string(), confidence))
+}
+
+fn detect_language_blocking(model_id: &str, samples: &[f32]) -> Result<(String, f32)> {
+    let model = get_or_load(model_id)?;
+    let model = model.lock();
+    match &*model {
+        LoadedModel::Whisper(ctx) => detect_language(ctx, samples),
+        LoadedModel::Parakeet(_) => anyhow::bail!("{} can't detect languages", model_id),
+    }
+}
+
+/// Pick the model for an "auto" recording. English (or any language the
+/// Parakeet model lists) goes to Parakeet, everything else to the configured
+/// Whisper model. Detection only runs when `language` is unset, and
+/// translation always needs Whisper. Without an installed model to detect
+/// with, or if detection fails, the recording goes to the default model.
+pub async fn route_auto(
+    app: &tauri::AppHandle,
+    samples: &[f32],
+    language: Option<&str>,
+    translate: bool,
+) -> RouteDecision {
+    let whisper_id = auto_whisper_model(app);
+    let route = |language: Option<String>, confidence: Option<f32>, detection_ms: u64| {
+        let parakeet = model_registry::find_model(model_registry::DEFAULT_MODEL_ID);
+        let use_parakeet = !translate
+            && language
+                .as_deref()
+                .zip(parakeet.as_ref())
+                .is_some_and(|(lang, m)| model_registry::supports_language(m, lang));
+        let model_id = if use_parakeet {
+            model_registry::DEFAULT_MODEL_ID.to_string()
+        } else {
+            whisper_id.clone()
+        };
+        RouteDecision { language, confidence, model_id, detection_ms }
+    };
+    let fallback = RouteDecision {
+        language: None,
+        confidence: None,
+        model_id: model_registry::DEFAULT_MODEL_ID.to_string(),
+        detection_ms: 0,
+    };
+
+    if translate {
+        return route(language.map(String::from), None, 0);
+    }
+    if let Some(lang) = language {
+        return route(Some(lang.to_string()), None, 0);
+    }
+
+    let Some(detector) = detection_model() else {
+        eprintln!(
+            "[audioshift] Auto route: no multilingual Whisper model installed -> {}",
+            fallback.model_id
+        );
+        return fallback;
+    };
+    let started = std::time::Instant::now();
+    let head: Vec<f32> = samples[..samples.len().min(DETECT_SECONDS * 16000)].to_vec();
+    let detected = tokio::task::spawn_blocking(move || detect_language_blocking(&detector, &head))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|r| r);
+    let (lang, confidence) = match detected {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("[audioshift] Auto route: language detection failed: {} -> {}", e, fallback.model_id);
+            return fallback;
+        }
+    };
+    let decision = route(Some(lang), Some(confidence), started.elapsed().as_millis() as u64);
+    eprintln!(
+        "[audioshift] Auto route: detected {:?} ({:.2}) in {}ms -> {}",
+        decision.language, confidence, decision.detection_ms, decision.model_id
+    );
+    decision
+}
+
 // --- Public transcribe entry point ---
 
+/// `prompt` primes Whisper with vocabulary or style; Parakeet ignores it.
 pub async fn transcribe_from_samples(
     app: &tauri::AppHandle,
     samples: Vec<f32>,
     model_id: &str,
     language: Option<String>,
     translate: bool,
+    prompt: Option<String>,
 ) -> Result<String> {
     ensure_model(app, model_id).await?;
 
     let _ = app.emit("status-changed", "transcribing");
 
-    let def = model_registry::find_model(model_id)
-        .with_context(|| format!("Unknown model: {}", model_id))?;
+    anyhow::ensure!(
+        model_registry::find_model(model_id).is_some(),
+        "Unknown model: {}",
+        model_id
+    );
 
-    let result = match def.engine {
-        Engine::Parakeet => {
-            tokio::task::spawn_blocking(move || transcribe_parakeet(samples)).await??
-        }
-        Engine::Whisper => {
-            let mid = model_id.to_string();
-            tokio::task::spawn_blocking(move || transcribe_whisper(samples, mid, language, translate)).await??
-        }
-    };
+    let mid = model_id.to_string();
+    tokio::task::spawn_blocking(move || transcribe_blocking(&mid, samples, language, translate, prompt))
+        .await?
+}
 
-    Ok(result)
+/// Transcribe with an already downloaded model without touching the app
+/// status, for work that runs behind the live transcription.
+pub async fn transcribe_in_background(
+    model_id: &str,
+    samples: Vec<f32>,
+    language: Option<String>,
+    translate: bool,
+    prompt: Option<String>,
+) -> Result<String> {
+    anyhow::ensure!(
+        model_registry::model_ready(model_id),
+        "Model {} is not downloaded",
+        model_id
+    );
+    let mid = model_id.to_string();
+    tokio::task::spawn_blocking(move || transcribe_blocking(&mid, samples, language, translate, prompt))
+        .await?
 }
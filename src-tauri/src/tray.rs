@@ -1,5 +1,7 @@
 use crate::commands;
 use crate::model_registry;
+use crate::paster;
+use crate::refine;
 use crate::state::{AppState, Status, TrayAnimation};
 use crate::tray_icons;
 use crate::windows;
@@ -159,6 +161,9 @@ pub fn build_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>
     let status_item = MenuItemBuilder::with_id("status", &status_text)
         .enabled(false)
         .build(app)?;
+    let refined_item = MenuItemBuilder::with_id("paste-refined", "Paste Refined Transcript")
+        .enabled(false)
+        .build(app)?;
     let settings_item =
         MenuItemBuilder::with_id("settings", "Settings").build(app)?;
     let quit_item = MenuItemBuilder::with_id("quit", "Quit AudioShift").build(app)?;
@@ -166,6 +171,7 @@ pub fn build_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>
     #[allow(unused_mut)]
     let mut menu_builder = MenuBuilder::new(app)
         .item(&status_item)
+        .item(&refined_item)
         .separator()
         .item(&settings_item);
 
@@ -192,6 +198,12 @@ pub fn build_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>
             "settings" => {
                 let _ = windows::create_settings_window(app);
             }
+            "paste-refined" => {
+                let mode = paster::stored_paste_mode(app);
+                if let Err(e) = refine::apply_last_refined(app, mode) {
+                    eprintln!("[audioshift] Failed to paste refined transcript: {}", e);
+                }
+            }
             #[cfg(feature = "updater")]
             "updates" => {
                 // Store pending section so fresh windows pick it up on mount
@@ -214,6 +226,7 @@ pub fn build_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>
 
     // Store tray handle for dynamic updates
     app.state::<AppState>().set_tray(tray, status_item);
+    app.state::<AppState>().set_tray_refined_item(refined_item);
     #[cfg(feature = "updater")]
     app.state::<AppState>().set_tray_updates_item(updates_item);
 
@@ -228,6 +241,14 @@ pub fn build_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>
         update_tray_for_status(&handle, status);
     });
 
+    // Offer the refined transcript once the second pass finishes
+    let handle = app.handle().clone();
+    app.listen("transcription-refined", move |_event| {
+        if let Some(item) = handle.state::<AppState>().tray_refined_item() {
+            let _ = item.set_enabled(true);
+        }
+    });
+
     // Listen for download progress to update tray status text
     let handle = app.handle().clone();
     app.listen("model-download-progress", move |event| {
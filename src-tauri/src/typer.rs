@@ -0,0 +1,177 @@
+//! "Type" output mode: the text is sent as Unicode keystrokes instead of
+//! being pasted, for terminals, remote desktop clients and web forms that
+//! ignore or block paste. The clipboard is left untouched.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const SETTINGS_KEY: &str = "typing";
+
+/// What a line break in the text turns into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Newline {
+    /// Return key.
+    Enter,
+    /// Shift+Return, a line break that doesn't send in chat apps.
+    ShiftEnter,
+    /// A space, for single-line fields.
+    Space,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TypingSettings {
+    /// Pause after each character; some remote clients drop fast input.
+    pub char_delay_ms: u64,
+    pub newline: Newline,
+}
+
+impl Default for TypingSettings {
+    fn default() -> Self {
+        Self {
+            char_delay_ms: 5,
+            newline: Newline::Enter,
+        }
+    }
+}
+
+pub fn load_settings(app: &AppHandle) -> TypingSettings {
+    app.store("settings.json")
+        .ok()
+        .and_then(|s| s.get(SETTINGS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &AppHandle, settings: &TypingSettings) -> Result<()> {
+    anyhow::ensure!(settings.char_delay_ms <= 1000, "Character delay must be at most 1000ms");
+    let store = app.store("settings.json")?;
+    store.set(SETTINGS_KEY, serde_json::to_value(settings)?);
+    Ok(())
+}
+
+/// Whether this platform can type text. Linux has no way yet to send
+/// arbitrary Unicode through XTest or uinput.
+pub fn supported() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+}
+
+/// A keystroke to send.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+enum Key {
+    Char(char),
+    Return { shift: bool },
+}
+
+fn keys(text: &str, newline: Newline) -> Vec<Key> {
+    let text = text.replace("\r\n", "\n");
+    text.chars()
+        .filter(|c| *c != '\r')
+        .map(|c| match (c, newline) {
+            ('\n', Newline::Enter) => Key::Return { shift: false },
+            ('\n', Newline::ShiftEnter) => Key::Return { shift: true },
+            ('\n', Newline::Space) => Key::Char(' '),
+            (c, _) => Key::Char(c),
+        })
+        .collect()
+}
+
+/// Type `text` into the focused app. Blocks until every keystroke is sent.
+pub fn type_text(text: &str, settings: &TypingSettings) -> Result<()> {
+    let delay = Duration::from_millis(settings.char_delay_ms);
+    for key in keys(text, settings.newline) {
+        send_key(&key)?;
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn send_key(key: &Key) -> Result<()> {
+    use core_graphics::event::{CGEvent, CGEventFlags, CGEventTapLocation};
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    const RETURN_KEY: u16 = 0x24;
+
+    let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState)
+        .map_err(|_| anyhow::anyhow!("Failed to create CGEventSource"))?;
+    let (keycode, flags) = match key {
+        Key::Return { shift: true } => (RETURN_KEY, CGEventFlags::CGEventFlagShift),
+        Key::Return { shift: false } => (RETURN_KEY, CGEventFlags::CGEventFlagNull),
+        // The keycode is ignored once a Unicode string is attached
+        Key::Char(_) => (0, CGEventFlags::CGEventFlagNull),
+    };
+    for down in [true, false] {
+        let event = CGEvent::new_keyboard_event(source.clone(), keycode, down)
+            .map_err(|_| anyhow::anyhow!("Failed to create key event"))?;
+        event.set_flags(flags);
+        if let Key::Char(c) = key {
+            let mut buf = [0u16; 2];
+            event.set_string_from_utf16_unchecked(c.encode_utf16(&mut buf));
+        }
+        event.post(CGEventTapLocation::Session);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn send_key(key: &Key) -> Result<()> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        KEYEVENTF_UNICODE, VIRTUAL_KEY,
+    };
+
+    const VK_RETURN: VIRTUAL_KEY = VIRTUAL_KEY(0x0D);
+    const VK_SHIFT: VIRTUAL_KEY = VIRTUAL_KEY(0x10);
+
+    let input = |vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: scan,
+                dwFlags: flags,
+                ..Default::default()
+            },
+        },
+    };
+
+    let mut inputs = Vec::new();
+    match key {
+        Key::Char(c) => {
+            // Characters outside the BMP are sent as a surrogate pair
+            let mut buf = [0u16; 2];
+            for unit in c.encode_utf16(&mut buf).iter() {
+                inputs.push(input(VIRTUAL_KEY(0), *unit, KEYEVENTF_UNICODE));
+                inputs.push(input(VIRTUAL_KEY(0), *unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+            }
+        }
+        Key::Return { shift } => {
+            if *shift {
+                inputs.push(input(VK_SHIFT, 0, KEYBD_EVENT_FLAGS(0)));
+            }
+            inputs.push(input(VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)));
+            inputs.push(input(VK_RETURN, 0, KEYEVENTF_KEYUP));
+            if *shift {
+                inputs.push(input(VK_SHIFT, 0, KEYEVENTF_KEYUP));
+            }
+        }
+    }
+
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) } as usize;
+    if sent != inputs.len() {
+        return Err(anyhow::anyhow!("SendInput failed, only sent {} of {} events", sent, inputs.len()));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn send_key(_key: &Key) -> Result<()> {
+    anyhow::bail!("Typing output is not supported on this platform yet")
+}
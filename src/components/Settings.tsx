@@ -30,7 +30,8 @@ export default function Settings() {
   const [devices, setDevices] = useState<string[]>([]);
   const [selectedDevice, setSelectedDevice] = useState<string>("");
   const [hotkey, setHotkey] = useState<string>("");
-  const [pasteMode, setPasteMode] = useState<"auto" | "clipboard">("auto");
+  const [pasteMode, setPasteMode] = useState<"auto" | "clipboard" | "type">("auto");
+  const [typingSupported, setTypingSupported] = useState(false);
   const [micPermission, setMicPermission] = useState<PermissionStatus>("checking");
   const [a11yPermission, setA11yPermission] = useState<PermissionStatus>("checking");
   const [autostart, setAutostart] = useState(false);
@@ -85,6 +86,7 @@ export default function Settings() {
     invoke<string>("get_live_model").then(setLiveModel);
     invoke<string>("get_transcription_language").then(setTranscriptionLanguage);
     invoke<boolean>("get_translate_to_english").then(setTranslateToEnglish);
+    invoke<boolean>("is_typing_supported").then(setTypingSupported);
     checkPermissions();
     loadAppSettings();
 
@@ -306,7 +308,7 @@ export default function Settings() {
         localStorage.setItem("overlayTheme", savedOverlayTheme);
       }
 
-      const savedPasteMode = await store.get<"auto" | "clipboard">("pasteMode");
+      const savedPasteMode = await store.get<"auto" | "clipboard" | "type">("pasteMode");
       if (savedPasteMode) {
         setPasteMode(savedPasteMode);
       }
@@ -454,11 +456,10 @@ export default function Settings() {
     }
   };
 
-  const handlePasteModeChange = async (mode: "auto" | "clipboard") => {
+  const handlePasteModeChange = async (mode: "auto" | "clipboard" | "type") => {
     setPasteMode(mode);
     try {
-      const store = await load("settings.json");
-      await store.set("pasteMode", mode);
+      await invoke("set_paste_mode", { mode });
     } catch (e) {
       console.error("Failed to save paste mode:", e);
     }
@@ -640,6 +641,7 @@ export default function Settings() {
         return (
           <OutputPage
             pasteMode={pasteMode}
+            typingSupported={typingSupported}
             onPasteModeChange={handlePasteModeChange}
           />
         );
@@ -3,11 +3,12 @@ import { Switch } from "@/components/ui/switch";
 import { SectionCard, SettingRow } from "./shared";
 
 interface Props {
-  pasteMode: "auto" | "clipboard";
-  onPasteModeChange: (mode: "auto" | "clipboard") => void;
+  pasteMode: "auto" | "clipboard" | "type";
+  typingSupported: boolean;
+  onPasteModeChange: (mode: "auto" | "clipboard" | "type") => void;
 }
 
-export default function OutputPage({ pasteMode, onPasteModeChange }: Props) {
+export default function OutputPage({ pasteMode, typingSupported, onPasteModeChange }: Props) {
   return (
     <div className="space-y-4">
       <SectionCard title="Paste Behavior" icon={<ClipboardPaste size={14} />}>
@@ -27,6 +28,20 @@ export default function OutputPage({ pasteMode, onPasteModeChange }: Props) {
             }
           />
         </SettingRow>
+        {typingSupported && (
+          <SettingRow
+            label="Type instead of paste"
+            description="Send the text as keystrokes for apps that block pasting"
+            note={pasteMode === "type" ? "Leaves the clipboard untouched" : undefined}
+          >
+            <Switch
+              checked={pasteMode === "type"}
+              onCheckedChange={(checked) =>
+                onPasteModeChange(checked ? "type" : "auto")
+              }
+            />
+          </SettingRow>
+        )}
       </SectionCard>
     </div>
   );